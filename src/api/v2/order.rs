@@ -24,6 +24,8 @@ use uuid::Uuid;
 
 use crate::api::v2::asset;
 use crate::util::vec_from_str;
+use crate::Client;
+use crate::RequestError;
 use crate::Str;
 
 
@@ -726,6 +728,78 @@ Endpoint! {
 }
 
 
+/// Check whether an error reported for an order submission leaves it
+/// unclear whether the order was created.
+fn is_ambiguous(err: &RequestError<CreateError>) -> bool {
+  match err {
+    RequestError::Endpoint(CreateError::UnexpectedStatus(status, ..)) => status.is_server_error(),
+    // The request may or may not have reached the server.
+    RequestError::Hyper(..)
+    | RequestError::HyperUtil(..)
+    | RequestError::Io(..)
+    | RequestError::Timeout
+    | RequestError::Transport(..) => true,
+    // Either the request was never sent or a definitive response was
+    // received. In the latter case the body could not be read, but
+    // looking up the order would most likely fail the same way.
+    RequestError::Endpoint(..)
+    | RequestError::UnsupportedEncoding(..)
+    | RequestError::BodyTooLarge(..)
+    | RequestError::LiveTradingDisabled
+    | RequestError::Credentials(..)
+    | RequestError::Middleware(..) => false,
+  }
+}
+
+
+/// Submit an order in an idempotent manner.
+///
+/// If the request does not have a client order ID set, a random one is
+/// generated. Should the submission fail in a way that leaves it
+/// unclear whether the order was created (e.g., because of a transport
+/// error, a timeout, or a server error), the order is looked up via
+/// its client order ID. If it exists, it is returned. Otherwise, the
+/// order is submitted once more, using the same client order ID. The
+/// server rejects duplicate client order IDs, which ensures that the
+/// order is never created twice. Should the resubmission fail, e.g.,
+/// because the server rejected the client order ID as a duplicate as
+/// the original submission got processed belatedly, the order is
+/// looked up one final time and returned if it exists.
+///
+/// If the ambiguity can not be resolved, the original error is
+/// reported.
+///
+/// This function is also available as
+/// [`Client::create_order_idempotent`].
+pub async fn create_idempotent(
+  client: &Client,
+  request: &CreateReq,
+) -> Result<Order, RequestError<CreateError>> {
+  let mut request = request.clone();
+  let client_order_id = request
+    .client_order_id
+    .get_or_insert_with(|| Uuid::new_v4().as_simple().to_string())
+    .clone();
+
+  match client.issue::<Create>(&request).await {
+    Err(err) if is_ambiguous(&err) => match client.issue::<GetByClientId>(&client_order_id).await {
+      Ok(order) => Ok(order),
+      Err(RequestError::Endpoint(GetByClientIdError::NotFound(..))) => {
+        match client.issue::<Create>(&request).await {
+          Ok(order) => Ok(order),
+          Err(err) => client
+            .issue::<GetByClientId>(&client_order_id)
+            .await
+            .map_err(|_| err),
+        }
+      },
+      Err(..) => Err(err),
+    },
+    result => result,
+  }
+}
+
+
 Endpoint! {
   /// The representation of a PATCH request to the /v2/orders/{order-id}
   /// endpoint.
@@ -801,9 +875,13 @@ mod tests {
   use super::*;
 
   use std::str::FromStr as _;
+  use std::sync::Arc;
 
   use futures::TryFutureExt;
 
+  use http::StatusCode;
+  use http_body_util::BodyExt as _;
+
   use serde_json::from_slice as from_json;
 
   use test_log::test;
@@ -815,10 +893,34 @@ mod tests {
   use crate::api::v2::asset::Symbol;
   use crate::api::v2::order_util::order_aapl;
   use crate::api_info::ApiInfo;
+  use crate::transport::test::response;
+  use crate::transport::test::Canned;
   use crate::Client;
   use crate::RequestError;
 
 
+  /// A JSON encoded order, as the server may report it.
+  const ORDER: &str = r#"{
+    "id": "904837e3-3b76-47ec-b432-046db621571b",
+    "client_order_id": "904837e3-3b76-47ec-b432-046db621571b",
+    "created_at": "2018-10-05T05:48:59Z",
+    "updated_at": "2018-10-05T05:48:59Z",
+    "submitted_at": "2018-10-05T05:48:59Z",
+    "asset_id": "904837e3-3b76-47ec-b432-046db621571b",
+    "symbol": "AAPL",
+    "asset_class": "us_equity",
+    "qty": "15",
+    "filled_qty": "0",
+    "type": "market",
+    "order_class": "simple",
+    "side": "buy",
+    "time_in_force": "day",
+    "status": "accepted",
+    "extended_hours": false,
+    "legs": null
+}"#;
+
+
   /// Check that we can serialize a [`Side`] object.
   #[test]
   fn emit_side() {
@@ -1463,6 +1565,171 @@ mod tests {
     };
   }
 
+  /// Create a client serving the provided canned responses.
+  fn canned_client<const N: usize>(
+    responses: [(StatusCode, &'static str); N],
+  ) -> (Client, Arc<Canned>) {
+    let api_info = ApiInfo::from_parts("https://example.com", "key-id", "secret").unwrap();
+    let transport = Canned::new(
+      responses
+        .into_iter()
+        .map(|(status, body)| response(status, body)),
+    );
+    let client = Client::builder()
+      .transport(transport.clone())
+      .build(api_info);
+    (client, transport)
+  }
+
+  /// Retrieve the client order ID of each order submission recorded by
+  /// the provided transport.
+  async fn submitted_client_order_ids(transport: &Canned) -> Vec<String> {
+    let bodies = {
+      let requests = transport.requests.lock().unwrap();
+      requests
+        .iter()
+        .filter(|request| request.method() == Method::POST)
+        .map(|request| request.body().clone())
+        .collect::<Vec<_>>()
+    };
+
+    let mut ids = Vec::new();
+    for body in bodies {
+      let body = body.collect().await.unwrap().to_bytes();
+      let request = from_json::<CreateReq>(&body).unwrap();
+      let () = ids.push(request.client_order_id.unwrap());
+    }
+    ids
+  }
+
+  /// Check that an idempotent order submission looks up the order
+  /// when the outcome of the submission is unclear.
+  #[test(tokio::test)]
+  async fn create_idempotent_lookup() {
+    let (client, transport) = canned_client([
+      (StatusCode::SERVICE_UNAVAILABLE, ""),
+      (StatusCode::OK, ORDER),
+    ]);
+    let request = CreateReqInit::default().init("AAPL", Side::Buy, Amount::quantity(15));
+    let order = create_idempotent(&client, &request).await.unwrap();
+    assert_eq!(order.symbol, "AAPL");
+
+    let ids = submitted_client_order_ids(&transport).await;
+    assert_eq!(ids.len(), 1);
+
+    let requests = transport.requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(
+      requests[1].uri().query(),
+      Some(format!("client_order_id={}", ids[0]).as_str())
+    );
+  }
+
+  /// Check that an idempotent order submission resubmits an order that
+  /// was not created, reusing the client order ID.
+  #[test(tokio::test)]
+  async fn create_idempotent_resubmit() {
+    let (client, transport) = canned_client([
+      (StatusCode::BAD_GATEWAY, ""),
+      (StatusCode::NOT_FOUND, r#"{"message":"order not found"}"#),
+      (StatusCode::OK, ORDER),
+    ]);
+    let request = CreateReq {
+      client_order_id: Some("my-id".to_string()),
+      ..CreateReqInit::default().init("AAPL", Side::Buy, Amount::quantity(15))
+    };
+    let _order = create_idempotent(&client, &request).await.unwrap();
+
+    let ids = submitted_client_order_ids(&transport).await;
+    assert_eq!(ids, vec!["my-id".to_string(), "my-id".to_string()]);
+  }
+
+  /// Check that an idempotent order submission looks up the order once
+  /// more if the resubmission got rejected, as happens when the
+  /// original submission got processed after all.
+  #[test(tokio::test)]
+  async fn create_idempotent_resubmit_duplicate() {
+    let (client, transport) = canned_client([
+      (StatusCode::GATEWAY_TIMEOUT, ""),
+      (StatusCode::NOT_FOUND, r#"{"message":"order not found"}"#),
+      (
+        StatusCode::UNPROCESSABLE_ENTITY,
+        r#"{"message":"client_order_id must be unique"}"#,
+      ),
+      (StatusCode::OK, ORDER),
+    ]);
+    let request = CreateReq {
+      client_order_id: Some("my-id".to_string()),
+      ..CreateReqInit::default().init("AAPL", Side::Buy, Amount::quantity(15))
+    };
+    let order = create_idempotent(&client, &request).await.unwrap();
+    assert_eq!(order.symbol, "AAPL");
+
+    let ids = submitted_client_order_ids(&transport).await;
+    assert_eq!(ids, vec!["my-id".to_string(), "my-id".to_string()]);
+
+    let requests = transport.requests.lock().unwrap();
+    assert_eq!(requests.len(), 4);
+    assert_eq!(requests[3].method(), Method::GET);
+    assert_eq!(requests[3].uri().query(), Some("client_order_id=my-id"));
+  }
+
+  /// Check that the error of a failed resubmission is reported if the
+  /// order can not be found afterwards either.
+  #[test(tokio::test)]
+  async fn create_idempotent_resubmit_error() {
+    let (client, _transport) = canned_client([
+      (StatusCode::GATEWAY_TIMEOUT, ""),
+      (StatusCode::NOT_FOUND, r#"{"message":"order not found"}"#),
+      (
+        StatusCode::UNPROCESSABLE_ENTITY,
+        r#"{"message":"insufficient buying power"}"#,
+      ),
+      (StatusCode::NOT_FOUND, r#"{"message":"order not found"}"#),
+    ]);
+    let request = CreateReqInit::default().init("AAPL", Side::Buy, Amount::quantity(15));
+    let err = create_idempotent(&client, &request).await.unwrap_err();
+    match err {
+      RequestError::Endpoint(CreateError::InvalidInput(..)) => (),
+      _ => panic!("Received unexpected error: {err:?}"),
+    }
+  }
+
+  /// Check that definitive errors are reported without further ado.
+  #[test(tokio::test)]
+  async fn create_idempotent_error() {
+    let (client, transport) = canned_client([(
+      StatusCode::UNPROCESSABLE_ENTITY,
+      r#"{"message":"insufficient buying power"}"#,
+    )]);
+    let request = CreateReqInit::default().init("AAPL", Side::Buy, Amount::quantity(15));
+    let err = client.create_order_idempotent(&request).await.unwrap_err();
+    match err {
+      RequestError::Endpoint(CreateError::InvalidInput(..)) => (),
+      _ => panic!("Received unexpected error: {err:?}"),
+    }
+    assert_eq!(transport.requests.lock().unwrap().len(), 1);
+  }
+
+  /// Check that only errors leaving the outcome of a submission unclear
+  /// are considered ambiguous.
+  #[test]
+  fn ambiguous_errors() {
+    use std::io::Error as IoError;
+    use std::io::ErrorKind;
+
+    assert!(is_ambiguous(&RequestError::Timeout));
+    assert!(is_ambiguous(&RequestError::Io(IoError::from(
+      ErrorKind::ConnectionReset
+    ))));
+    assert!(!is_ambiguous(&RequestError::BodyTooLarge(16)));
+    assert!(!is_ambiguous(&RequestError::UnsupportedEncoding(
+      "foo".to_string()
+    )));
+    assert!(!is_ambiguous(&RequestError::LiveTradingDisabled));
+    assert!(!is_ambiguous(&RequestError::Middleware("denied".into())));
+  }
+
   /// Test that we can change the client order ID of an order.
   #[test(tokio::test)]
   async fn change_client_order_id() {
@@ -33,9 +33,13 @@ use websocket_util::wrap::Wrapper;
 
 use crate::api::v2::order;
 use crate::api_info::ApiInfo;
+use crate::credentials::Credentials;
+use crate::secret::Secret;
 use crate::subscribable::Subscribable;
+use crate::telemetry;
 use crate::websocket::connect;
 use crate::websocket::MessageResult;
+use crate::websocket::StreamConfig;
 use crate::Error;
 
 
@@ -179,13 +183,29 @@ pub enum Authenticate<'d> {
   #[serde(rename = "auth")]
   Request {
     #[serde(rename = "key")]
-    key_id: Cow<'d, str>,
+    key_id: Cow<'d, Secret>,
     #[serde(rename = "secret")]
-    secret: Cow<'d, str>,
+    secret: Cow<'d, Secret>,
+  },
+  /// A request to authenticate with the server using an OAuth access
+  /// token.
+  #[serde(rename = "authenticate")]
+  OAuthRequest {
+    #[serde(rename = "data")]
+    data: OAuthToken<'d>,
   },
 }
 
 
+/// The payload of an OAuth based authentication request.
+#[derive(Debug, Deserialize, Serialize)]
+#[doc(hidden)]
+pub struct OAuthToken<'d> {
+  #[serde(rename = "oauth_token")]
+  token: Cow<'d, Secret>,
+}
+
+
 /// A control message listen request sent over a websocket channel.
 #[derive(Debug, Deserialize, Serialize)]
 // Part of unofficial unstable API.
@@ -229,6 +249,17 @@ pub enum OrderMessage {
   ListeningMessage(Streams<'static>),
 }
 
+impl OrderMessage {
+  /// Retrieve a textual representation of the message's kind.
+  fn kind(&self) -> &'static str {
+    match self {
+      Self::OrderUpdate(..) => "trade_updates",
+      Self::AuthenticationMessage(..) => "authorization",
+      Self::ListeningMessage(..) => "listening",
+    }
+  }
+}
+
 
 /// A representation of an order update that we receive through the
 /// "trade_updates" stream.
@@ -294,12 +325,18 @@ where
   /// Authenticate the connection using Alpaca credentials.
   async fn authenticate(
     &mut self,
-    key_id: &str,
-    secret: &str,
+    credentials: &Credentials,
   ) -> Result<Result<(), Error>, S::Error> {
-    let request = Authenticate::Request {
-      key_id: key_id.into(),
-      secret: secret.into(),
+    let request = match credentials {
+      Credentials::KeyPair { key_id, secret } => Authenticate::Request {
+        key_id: Cow::Borrowed(key_id),
+        secret: Cow::Borrowed(secret),
+      },
+      Credentials::OAuth { token } => Authenticate::OAuthRequest {
+        data: OAuthToken {
+          token: Cow::Borrowed(token),
+        },
+      },
     };
     let json = match to_json(&request) {
       Ok(json) => json,
@@ -379,27 +416,41 @@ impl Subscribable for OrderUpdates {
   type Stream = Fuse<MessageStream<SplitStream<Stream>, ParsedMessage>>;
 
   async fn connect(api_info: &Self::Input) -> Result<(Self::Stream, Self::Subscription), Error> {
+    Self::connect_with(api_info, &StreamConfig::default()).await
+  }
+
+  async fn connect_with(
+    api_info: &Self::Input,
+    config: &StreamConfig,
+  ) -> Result<(Self::Stream, Self::Subscription), Error> {
     fn map(result: Result<wrap::Message, WebSocketError>) -> ParsedMessage {
-      MessageResult::from(result.map(|message| match message {
-        wrap::Message::Text(string) => json_from_str::<OrderMessage>(&string),
-        wrap::Message::Binary(data) => json_from_slice::<OrderMessage>(&data),
+      MessageResult::from(result.map(|message| {
+        let result = match message {
+          wrap::Message::Text(string) => json_from_str::<OrderMessage>(&string),
+          wrap::Message::Binary(data) => json_from_slice::<OrderMessage>(&data),
+        };
+
+        match &result {
+          Ok(message) => telemetry::record_stream_message("updates", message.kind()),
+          Err(..) => telemetry::record_stream_parse_error("updates"),
+        }
+        result
       }))
     }
 
     let ApiInfo {
       api_stream_url: url,
-      key_id,
-      secret,
+      credentials,
       ..
     } = api_info;
 
-    let stream = connect(url).await?.map(map as MapFn);
+    let stream = connect(url, config).await?.map(map as MapFn);
     let (send, recv) = stream.split();
     let (stream, subscription) = subscribe::subscribe(recv, send);
     let mut stream = stream.fuse();
 
     let mut subscription = Subscription(subscription);
-    let authenticate = subscription.authenticate(key_id, secret).boxed();
+    let authenticate = subscription.authenticate(credentials).boxed();
     let () = subscribe::drive::<ParsedMessage, _, _>(authenticate, &mut stream)
       .await
       .map_err(|result| {
@@ -419,6 +470,7 @@ impl Subscribable for OrderUpdates {
           .unwrap_or_else(|err| err)
       })???;
 
+    let () = telemetry::record_stream_connect("updates");
     Ok((stream, subscription))
   }
 }
@@ -464,8 +516,8 @@ mod tests {
   /// Check that we can encode an authentication request correctly.
   #[test]
   fn encode_authentication_request() {
-    let key_id = "some-key".into();
-    let secret = "super-secret-secret".into();
+    let key_id = Cow::Owned(Secret::from("some-key"));
+    let secret = Cow::Owned(Secret::from("super-secret-secret"));
     let expected = r#"{"action":"auth","key":"some-key","secret":"super-secret-secret"}"#;
 
     let request = Authenticate::Request { key_id, secret };
@@ -473,6 +525,21 @@ mod tests {
     assert_eq!(json, expected)
   }
 
+  /// Check that we can encode an OAuth based authentication request
+  /// correctly.
+  #[test]
+  fn encode_oauth_authentication_request() {
+    let expected = r#"{"action":"authenticate","data":{"oauth_token":"some-token"}}"#;
+
+    let request = Authenticate::OAuthRequest {
+      data: OAuthToken {
+        token: Cow::Owned(Secret::from("some-token")),
+      },
+    };
+    let json = to_json(&request).unwrap();
+    assert_eq!(json, expected)
+  }
+
   /// Check that we can encode a listen request properly.
   #[test]
   fn encode_listen_request() {
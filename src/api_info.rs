@@ -1,24 +1,49 @@
 // Copyright (C) 2019-2023 The apca Developers
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+#[cfg(feature = "config")]
+use std::collections::HashMap;
 use std::env::var_os;
 use std::ffi::OsString;
+#[cfg(feature = "config")]
+use std::fs::read_to_string;
+#[cfg(feature = "config")]
+use std::path::Path;
+#[cfg(feature = "config")]
+use std::path::PathBuf;
+
+#[cfg(feature = "config")]
+use serde::Deserialize;
 
 use url::Url;
 
 use crate::api::API_BASE_URL;
+use crate::credentials::Credentials;
+use crate::data::v2::Feed;
 use crate::data::DATA_BASE_URL;
 use crate::data::DATA_STREAM_BASE_URL;
 use crate::Error;
+#[cfg(feature = "config")]
+use crate::Str;
 
 /// The base URL of the Trading API to use.
 const ENV_API_BASE_URL: &str = "APCA_API_BASE_URL";
 /// The URL of the websocket stream portion of the Trading API to use.
 const ENV_API_STREAM_URL: &str = "APCA_API_STREAM_URL";
-/// The environment variable representing the key ID.
-const ENV_KEY_ID: &str = "APCA_API_KEY_ID";
-/// The environment variable representing the secret key.
-const ENV_SECRET: &str = "APCA_API_SECRET_KEY";
+/// The base URL of the Data API to use.
+const ENV_DATA_URL: &str = "APCA_API_DATA_URL";
+/// The URL of the websocket stream portion of the Data API to use.
+const ENV_DATA_STREAM_URL: &str = "APCA_API_DATA_STREAM_URL";
+/// The host name of the paper trading Trading API.
+const PAPER_API_HOST: &str = "paper-api.alpaca.markets";
+/// The host name of the live trading Trading API.
+const LIVE_API_HOST: &str = "api.alpaca.markets";
+/// The name of the profile to use from the configuration file.
+#[cfg(feature = "config")]
+const ENV_PROFILE: &str = "APCA_PROFILE";
+/// The name of the profile used if none was selected explicitly.
+#[cfg(feature = "config")]
+const DEFAULT_PROFILE: &str = "default";
 
 
 /// Convert a Trading API base URL into the corresponding one for
@@ -33,6 +58,119 @@ fn make_api_stream_url(base_url: Url) -> Result<Url, Error> {
 }
 
 
+/// Retrieve a URL from the environment variable with the given name,
+/// falling back to the provided default if it is not set.
+fn url_from_env(name: &str, default: &str) -> Result<Url, Error> {
+  match var_os(name) {
+    Some(url) => {
+      let url = url.into_string().map_err(|_| {
+        Error::Str(format!("{name} environment variable is not a valid string").into())
+      })?;
+      Ok(Url::parse(&url)?)
+    },
+    None => Ok(Url::parse(default)?),
+  }
+}
+
+
+/// Retrieve the path to the default configuration file, i.e.,
+/// `$XDG_CONFIG_HOME/apca/config.toml`, falling back to
+/// `$HOME/.config/apca/config.toml`.
+#[cfg(feature = "config")]
+fn default_config_path() -> Result<PathBuf, Error> {
+  let config_dir = var_os("XDG_CONFIG_HOME")
+    .filter(|dir| !dir.is_empty())
+    .map(PathBuf::from)
+    .or_else(|| var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    .ok_or_else(|| Error::Str("unable to determine configuration directory".into()))?;
+  Ok(config_dir.join("apca").join("config.toml"))
+}
+
+
+/// A single profile as it appears in the configuration file.
+///
+/// All members are optional: URLs not provided fall back to the same
+/// defaults used by [`ApiInfo::from_env`].
+#[cfg(feature = "config")]
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Profile {
+  api_base_url: Option<String>,
+  api_stream_url: Option<String>,
+  data_base_url: Option<String>,
+  data_stream_base_url: Option<String>,
+  feed: Option<Feed>,
+  key_id: Option<String>,
+  secret: Option<String>,
+  oauth_token: Option<String>,
+}
+
+#[cfg(feature = "config")]
+impl Profile {
+  /// Convert the profile into an `ApiInfo` object.
+  fn into_api_info(self) -> Result<ApiInfo, Str> {
+    let parse = |name: &str, url: Option<String>, default: &str| {
+      let url = url.as_deref().unwrap_or(default);
+      Url::parse(url).map_err(|err| Str::from(format!("invalid {name} {url}: {err}")))
+    };
+
+    let api_base_url = parse("api_base_url", self.api_base_url, API_BASE_URL)?;
+    let api_stream_url = match self.api_stream_url {
+      Some(url) => parse("api_stream_url", Some(url), "")?,
+      // If no explicit websocket URL was configured then infer the one
+      // to use based on the API base URL.
+      None => make_api_stream_url(api_base_url.clone()).map_err(|err| err.to_string())?,
+    };
+    let data_base_url = parse("data_base_url", self.data_base_url, DATA_BASE_URL)?;
+    let data_stream_base_url = parse(
+      "data_stream_base_url",
+      self.data_stream_base_url,
+      DATA_STREAM_BASE_URL,
+    )?;
+
+    let credentials = match (self.oauth_token, self.key_id, self.secret) {
+      (Some(token), ..) => Credentials::oauth(token),
+      (None, Some(key_id), Some(secret)) => Credentials::key_pair(key_id, secret),
+      (None, None, _) => return Err("key_id not found".into()),
+      (None, Some(_), None) => return Err("secret not found".into()),
+    };
+
+    Ok(ApiInfo {
+      api_base_url,
+      api_stream_url,
+      data_base_url,
+      data_stream_base_url,
+      feed: self.feed,
+      credentials,
+    })
+  }
+}
+
+
+/// The contents of a configuration file.
+#[cfg(feature = "config")]
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Config {
+  #[serde(default)]
+  profiles: HashMap<String, Profile>,
+}
+
+
+/// The environment an [`ApiInfo`] object targets.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum Environment {
+  /// The paper trading environment, operating on simulated accounts.
+  Paper,
+  /// The live trading environment, operating on real accounts.
+  Live,
+  /// An environment other than Alpaca's paper or live one, e.g., a
+  /// proxy or a mock server.
+  Custom,
+}
+
+
 /// An object encapsulating the information used for working with the
 /// Alpaca API.
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -46,10 +184,15 @@ pub struct ApiInfo {
   pub data_base_url: Url,
   /// The websocket base URL for streaming of data.
   pub data_stream_base_url: Url,
-  /// The key ID to use for authentication.
-  pub key_id: String,
-  /// The secret to use for authentication.
-  pub secret: String,
+  /// The market data feed to use, if configured explicitly.
+  ///
+  /// If set, this feed is used for stock market data requests that do
+  /// not specify one themselves as well as for real time data streamed
+  /// via the [`ConfiguredFeed`][crate::data::v2::stream::ConfiguredFeed]
+  /// source.
+  pub feed: Option<Feed>,
+  /// The credentials to use for authentication.
+  pub credentials: Credentials,
 }
 
 impl ApiInfo {
@@ -64,6 +207,20 @@ impl ApiInfo {
     api_base_url: impl AsRef<str>,
     key_id: impl ToString,
     secret: impl ToString,
+  ) -> Result<Self, Error> {
+    Self::from_credentials(api_base_url, Credentials::key_pair(key_id, secret))
+  }
+
+  /// Create an `ApiInfo` from a base URL and the credentials to use.
+  /// Note that using this constructor the websocket URL will be
+  /// inferred based on the base URL provided.
+  ///
+  /// # Errors
+  /// - [`Error::Url`](crate::Error::Url) If `api_base_url` cannot be parsed
+  ///   into a [`url::Url`](url::Url).
+  pub fn from_credentials(
+    api_base_url: impl AsRef<str>,
+    credentials: Credentials,
   ) -> Result<Self, Error> {
     let api_base_url = Url::parse(api_base_url.as_ref())?;
     let api_stream_url = make_api_stream_url(api_base_url.clone())?;
@@ -76,11 +233,21 @@ impl ApiInfo {
       // fine.
       data_base_url: Url::parse(DATA_BASE_URL).unwrap(),
       data_stream_base_url: Url::parse(DATA_STREAM_BASE_URL).unwrap(),
-      key_id: key_id.to_string(),
-      secret: secret.to_string(),
+      feed: None,
+      credentials,
     })
   }
 
+  /// Determine the [`Environment`] targeted, based on the Trading API
+  /// base URL.
+  pub fn environment(&self) -> Environment {
+    match self.api_base_url.host_str() {
+      Some(PAPER_API_HOST) => Environment::Paper,
+      Some(LIVE_API_HOST) => Environment::Live,
+      _ => Environment::Custom,
+    }
+  }
+
   /// Create an `ApiInfo` object with information from the environment.
   ///
   /// This constructor retrieves API related information from the
@@ -90,14 +257,19 @@ impl ApiInfo {
   ///   `APCA_API_BASE_URL` variable
   /// - the Alpaca Trading API stream URL is retrieved from the
   ///   `APCA_API_STREAM_URL` variable
+  /// - the Alpaca Data API base URL is retrieved from the
+  ///   `APCA_API_DATA_URL` variable
+  /// - the Alpaca Data API stream URL is retrieved from the
+  ///   `APCA_API_DATA_STREAM_URL` variable
   /// - the Alpaca account key ID is retrieved from the
   ///   `APCA_API_KEY_ID` variable
   /// - the Alpaca account secret is retrieved from the
   ///   `APCA_API_SECRET_KEY` variable
+  /// - alternatively to the key ID and secret, an OAuth access token is
+  ///   retrieved from the `APCA_API_OAUTH_TOKEN` variable, if set
   ///
-  /// # Notes
-  /// - Neither of the two data APIs can be configured via the
-  ///   environment currently; defaults will be used
+  /// All URLs are optional and default to the ones used for the live
+  /// Alpaca API.
   #[allow(unused_qualifications)]
   pub fn from_env() -> Result<Self, Error> {
     let api_base_url = var_os(ENV_API_BASE_URL)
@@ -124,32 +296,97 @@ impl ApiInfo {
       })?;
     let api_stream_url = Url::parse(&api_stream_url)?;
 
-    let key_id = var_os(ENV_KEY_ID)
-      .ok_or_else(|| Error::Str(format!("{ENV_KEY_ID} environment variable not found").into()))?
-      .into_string()
-      .map_err(|_| {
-        Error::Str(format!("{ENV_KEY_ID} environment variable is not a valid string").into())
-      })?;
+    let data_base_url = url_from_env(ENV_DATA_URL, DATA_BASE_URL)?;
+    let data_stream_base_url = url_from_env(ENV_DATA_STREAM_URL, DATA_STREAM_BASE_URL)?;
 
-    let secret = var_os(ENV_SECRET)
-      .ok_or_else(|| Error::Str(format!("{ENV_SECRET} environment variable not found").into()))?
-      .into_string()
-      .map_err(|_| {
-        Error::Str(format!("{ENV_SECRET} environment variable is not a valid string").into())
-      })?;
+    let credentials = Credentials::from_env()?;
 
     Ok(Self {
       api_base_url,
       api_stream_url,
-      // We basically only work with statically defined URL parts here
-      // which we know can be parsed successfully, so unwrapping is
-      // fine.
-      data_base_url: Url::parse(DATA_BASE_URL).unwrap(),
-      data_stream_base_url: Url::parse(DATA_STREAM_BASE_URL).unwrap(),
-      key_id,
-      secret,
+      data_base_url,
+      data_stream_base_url,
+      feed: None,
+      credentials,
     })
   }
+
+  /// Create an `ApiInfo` object from a profile in the default
+  /// configuration file.
+  ///
+  /// The configuration file is located at
+  /// `$XDG_CONFIG_HOME/apca/config.toml` or, if `XDG_CONFIG_HOME` is
+  /// not set, at `$HOME/.config/apca/config.toml`. The profile to use
+  /// is selected via the `APCA_PROFILE` environment variable and
+  /// defaults to `default`.
+  ///
+  /// Please refer to [`ApiInfo::from_config_file`] for details on the
+  /// file's format.
+  #[cfg(feature = "config")]
+  pub fn from_config() -> Result<Self, Error> {
+    let path = default_config_path()?;
+    let profile = var_os(ENV_PROFILE)
+      .map(|profile| {
+        profile.into_string().map_err(|_| {
+          Error::Str(format!("{ENV_PROFILE} environment variable is not a valid string").into())
+        })
+      })
+      .transpose()?;
+
+    Self::from_config_file(path, profile.as_deref().unwrap_or(DEFAULT_PROFILE))
+  }
+
+  /// Create an `ApiInfo` object from the profile with the given name
+  /// in the provided configuration file.
+  ///
+  /// The file is in TOML format and contains a `profiles` table with
+  /// one sub-table per profile, e.g.:
+  /// ```toml
+  /// [profiles.default]
+  /// api_base_url = "https://paper-api.alpaca.markets"
+  /// key_id = "XXXXXXXXXXXXXXXXXXXX"
+  /// secret = "YYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYY"
+  ///
+  /// [profiles.live]
+  /// api_base_url = "https://api.alpaca.markets"
+  /// data_base_url = "https://data.alpaca.markets"
+  /// feed = "sip"
+  /// oauth_token = "ZZZZZZZZ-ZZZZ-ZZZZ-ZZZZ-ZZZZZZZZZZZZ"
+  /// ```
+  ///
+  /// The following keys are supported per profile:
+  /// - `api_base_url`, `api_stream_url`, `data_base_url`, and
+  ///   `data_stream_base_url`, all optional; the API stream URL is
+  ///   inferred based on the API base URL if not provided and the
+  ///   remaining ones default to the same values used by
+  ///   [`ApiInfo::from_env`]
+  /// - `feed`, the optional market data feed to use (`iex` or `sip`);
+  ///   see [`ApiInfo::feed`]
+  /// - `key_id` and `secret` or, alternatively, `oauth_token`
+  #[cfg(feature = "config")]
+  pub fn from_config_file(path: impl AsRef<Path>, profile: &str) -> Result<Self, Error> {
+    let path = path.as_ref();
+    let contents = read_to_string(path).map_err(|err| {
+      Error::Str(format!("{}: failed to read file: {err}", path.display()).into())
+    })?;
+
+    Self::from_config_contents(&contents, profile)
+      .map_err(|err| Error::Str(format!("{}: {err}", path.display()).into()))
+  }
+
+  /// Create an `ApiInfo` object from the profile with the given name in
+  /// the provided configuration file contents.
+  #[cfg(feature = "config")]
+  fn from_config_contents(contents: &str, profile: &str) -> Result<Self, Str> {
+    let mut config = toml::from_str::<Config>(contents)
+      .map_err(|err| Str::from(format!("failed to parse configuration: {err}")))?;
+    config
+      .profiles
+      .remove(profile)
+      .ok_or_else(|| Str::from(format!("profile {profile} not found")))?
+      .into_api_info()
+      .map_err(|err| format!("profile {profile}: {err}").into())
+  }
 }
 
 
@@ -168,7 +405,112 @@ mod tests {
 
     let api_info = ApiInfo::from_parts(api_base_url, key_id, secret).unwrap();
     assert_eq!(api_info.api_base_url.as_str(), api_base_url);
-    assert_eq!(api_info.key_id, key_id);
-    assert_eq!(api_info.secret, secret);
+    assert_eq!(api_info.credentials, Credentials::key_pair(key_id, secret));
+
+    // Neither the key ID nor the secret should be revealed when
+    // formatting the object.
+    let debug = format!("{api_info:?}");
+    assert!(!debug.contains(key_id), "{debug}");
+    assert!(!debug.contains(secret), "{debug}");
+  }
+
+  /// Check that we can create an [`ApiInfo`] object using OAuth
+  /// credentials.
+  #[test]
+  fn from_oauth_credentials() {
+    let api_base_url = "https://api.alpaca.markets/";
+    let credentials = Credentials::oauth("ZZZZZZZZ-ZZZZ-ZZZZ-ZZZZ-ZZZZZZZZZZZZ");
+
+    let api_info = ApiInfo::from_credentials(api_base_url, credentials.clone()).unwrap();
+    assert_eq!(
+      api_info.api_stream_url.as_str(),
+      "wss://api.alpaca.markets/stream"
+    );
+    assert_eq!(api_info.credentials, credentials);
+  }
+
+  /// Check that we correctly determine the environment targeted.
+  #[test]
+  fn environment() {
+    let environment = |url| {
+      ApiInfo::from_parts(url, "key-id", "secret")
+        .unwrap()
+        .environment()
+    };
+
+    assert_eq!(
+      environment("https://paper-api.alpaca.markets"),
+      Environment::Paper
+    );
+    assert_eq!(
+      environment("https://api.alpaca.markets/"),
+      Environment::Live
+    );
+    assert_eq!(environment("http://localhost:8080"), Environment::Custom);
+    assert_eq!(
+      environment("https://api.alpaca.markets.example.com"),
+      Environment::Custom
+    );
+  }
+
+  /// Check that we can create [`ApiInfo`] objects from profiles in a
+  /// configuration file.
+  #[cfg(feature = "config")]
+  #[test]
+  fn from_config_contents() {
+    let contents = r#"
+[profiles.default]
+api_base_url = "https://paper-api.alpaca.markets"
+key_id = "XXXXXXXXXXXXXXXXXXXX"
+secret = "YYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYY"
+
+[profiles.live]
+api_base_url = "https://api.alpaca.markets"
+data_stream_base_url = "wss://stream.data.sandbox.alpaca.markets"
+feed = "sip"
+oauth_token = "ZZZZZZZZ-ZZZZ-ZZZZ-ZZZZ-ZZZZZZZZZZZZ"
+
+[profiles.incomplete]
+key_id = "XXXXXXXXXXXXXXXXXXXX"
+"#;
+
+    let api_info = ApiInfo::from_config_contents(contents, "default").unwrap();
+    assert_eq!(
+      api_info.api_stream_url.as_str(),
+      "wss://paper-api.alpaca.markets/stream"
+    );
+    assert_eq!(
+      api_info.data_base_url.as_str(),
+      Url::parse(DATA_BASE_URL).unwrap().as_str()
+    );
+    assert_eq!(api_info.feed, None);
+    assert_eq!(
+      api_info.credentials,
+      Credentials::key_pair(
+        "XXXXXXXXXXXXXXXXXXXX",
+        "YYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYY"
+      )
+    );
+
+    let api_info = ApiInfo::from_config_contents(contents, "live").unwrap();
+    assert_eq!(
+      api_info.api_stream_url.as_str(),
+      "wss://api.alpaca.markets/stream"
+    );
+    assert_eq!(
+      api_info.data_stream_base_url.as_str(),
+      "wss://stream.data.sandbox.alpaca.markets/"
+    );
+    assert_eq!(api_info.feed, Some(Feed::SIP));
+    assert_eq!(
+      api_info.credentials,
+      Credentials::oauth("ZZZZZZZZ-ZZZZ-ZZZZ-ZZZZ-ZZZZZZZZZZZZ")
+    );
+
+    let err = ApiInfo::from_config_contents(contents, "incomplete").unwrap_err();
+    assert_eq!(err, "profile incomplete: secret not found");
+
+    let err = ApiInfo::from_config_contents(contents, "paper").unwrap_err();
+    assert_eq!(err, "profile paper not found");
   }
 }
@@ -0,0 +1,239 @@
+// Copyright (C) 2024 The apca Developers
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! A synchronous facade for the [`Client`][crate::Client].
+//!
+//! The [`Client`] provided here owns a single-threaded `tokio` runtime
+//! on which it drives all operations, blocking the calling thread in
+//! the process. As such, it must not be used from within an
+//! asynchronous context.
+//!
+//! ```no_run
+//! use apca::api::v2::account;
+//! use apca::blocking::Client;
+//! use apca::data::v2::stream::drive;
+//! use apca::data::v2::stream::MarketData;
+//! use apca::data::v2::stream::RealtimeData;
+//! use apca::data::v2::stream::IEX;
+//! use apca::ApiInfo;
+//!
+//! use futures::FutureExt as _;
+//!
+//! let api_info = ApiInfo::from_env().unwrap();
+//! let client = Client::new(api_info).unwrap();
+//! let account = client.issue::<account::Get>(&()).unwrap();
+//! println!("cash:\t{} {}", account.cash, account.currency);
+//!
+//! let (mut stream, mut subscription) = client.subscribe::<RealtimeData<IEX>>().unwrap();
+//! let mut data = MarketData::default();
+//! data.set_bars(["AAPL"]);
+//!
+//! let subscribe = subscription.subscribe(&data).boxed();
+//! let () = client
+//!   .block_on(drive(subscribe, stream.get_mut()))
+//!   .unwrap()
+//!   .unwrap()
+//!   .unwrap();
+//!
+//! for result in stream.take(10) {
+//!   println!("{result:?}");
+//! }
+//! ```
+
+use std::future::Future;
+use std::io::Result as IoResult;
+
+use futures::StreamExt as _;
+
+use http_endpoint::Endpoint;
+
+use tokio::runtime::Builder as RuntimeBuilder;
+use tokio::runtime::Runtime;
+
+use crate::ApiInfo;
+use crate::Client as AsyncClient;
+use crate::Error;
+use crate::RequestError;
+use crate::RequestErrorWithMeta;
+use crate::ResponseMeta;
+use crate::Subscribable;
+
+
+/// A blocking client for interacting with the Alpaca API.
+#[derive(Debug)]
+pub struct Client {
+  /// The asynchronous client we wrap.
+  client: AsyncClient,
+  /// The runtime we use for driving operations to completion.
+  runtime: Runtime,
+}
+
+impl Client {
+  /// Create a new `Client` using the given key ID and secret for
+  /// connecting to the API.
+  pub fn new(api_info: ApiInfo) -> IoResult<Self> {
+    Self::from_client(AsyncClient::new(api_info))
+  }
+
+  /// Create a new `Client` wrapping the provided asynchronous one,
+  /// e.g., to use custom settings configured via
+  /// [`Client::builder`][crate::Client::builder].
+  pub fn from_client(client: AsyncClient) -> IoResult<Self> {
+    let runtime = RuntimeBuilder::new_current_thread().enable_all().build()?;
+    Ok(Self { client, runtime })
+  }
+
+  /// Run the provided future to completion, blocking the calling
+  /// thread in the meantime.
+  ///
+  /// This method can be used for invoking asynchronous functionality
+  /// not directly exposed, such as changing subscriptions of a stream.
+  #[inline]
+  pub fn block_on<F>(&self, future: F) -> F::Output
+  where
+    F: Future,
+  {
+    self.runtime.block_on(future)
+  }
+
+  /// Create and issue a request and decode the response.
+  ///
+  /// Please refer to [`Client::issue`][crate::Client::issue] for
+  /// details.
+  #[inline]
+  #[allow(clippy::result_large_err)]
+  pub fn issue<R>(&self, input: &R::Input) -> Result<R::Output, RequestError<R::Error>>
+  where
+    R: Endpoint,
+  {
+    self.block_on(self.client.issue::<R>(input))
+  }
+
+  /// Create and issue a request and decode the response, along with
+  /// metadata about the response.
+  ///
+  /// Please refer to
+  /// [`Client::issue_with_meta`][crate::Client::issue_with_meta] for
+  /// details.
+  #[inline]
+  #[allow(clippy::result_large_err)]
+  pub fn issue_with_meta<R>(
+    &self,
+    input: &R::Input,
+  ) -> Result<(R::Output, ResponseMeta), RequestErrorWithMeta<R::Error>>
+  where
+    R: Endpoint,
+  {
+    self.block_on(self.client.issue_with_meta::<R>(input))
+  }
+
+  /// Subscribe to the given subscribable in order to receive updates.
+  ///
+  /// Messages are retrieved by iterating over the returned [`Stream`].
+  pub fn subscribe<S>(&self) -> Result<(Stream<'_, S::Stream>, S::Subscription), Error>
+  where
+    S: Subscribable<Input = ApiInfo> + Send,
+  {
+    let (stream, subscription) = self.block_on(self.client.subscribe::<S>())?;
+    let stream = Stream {
+      runtime: &self.runtime,
+      stream,
+    };
+    Ok((stream, subscription))
+  }
+
+  /// Retrieve the wrapped asynchronous client.
+  #[inline]
+  pub fn as_async(&self) -> &AsyncClient {
+    &self.client
+  }
+
+  /// Retrieve the `ApiInfo` object used by this `Client` instance.
+  #[inline]
+  pub fn api_info(&self) -> &ApiInfo {
+    self.client.api_info()
+  }
+}
+
+
+/// A blocking iterator over the messages received via a stream, as
+/// produced by [`Client::subscribe`].
+#[derive(Debug)]
+pub struct Stream<'c, S> {
+  /// The runtime to use for polling the stream.
+  runtime: &'c Runtime,
+  /// The wrapped stream.
+  stream: S,
+}
+
+impl<S> Stream<'_, S> {
+  /// Retrieve a mutable reference to the wrapped asynchronous stream,
+  /// e.g., for driving subscription changes via
+  /// [`Client::block_on`].
+  #[inline]
+  pub fn get_mut(&mut self) -> &mut S {
+    &mut self.stream
+  }
+
+  /// Unwrap the asynchronous stream.
+  #[inline]
+  pub fn into_inner(self) -> S {
+    self.stream
+  }
+}
+
+impl<S> Iterator for Stream<'_, S>
+where
+  S: futures::Stream + Unpin,
+{
+  type Item = S::Item;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.runtime.block_on(self.stream.next())
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use futures::stream::iter;
+
+  use http::StatusCode;
+
+  use crate::api::v2::clock;
+  use crate::transport::test::response;
+  use crate::transport::test::Canned;
+
+
+  /// Check that we can issue requests using the blocking client.
+  #[test]
+  fn blocking_issue() {
+    let body = r#"{
+  "timestamp": "2018-04-01T12:00:00.000Z",
+  "is_open": true,
+  "next_open": "2018-04-01T12:00:00.000Z",
+  "next_close": "2018-04-01T12:00:00.000Z"
+}"#;
+    let api_info = ApiInfo::from_parts("https://example.com", "key-id", "secret").unwrap();
+    let transport = Canned::new([response(StatusCode::OK, body)]);
+    let client = AsyncClient::builder().transport(transport).build(api_info);
+    let client = Client::from_client(client).unwrap();
+
+    let clock = client.issue::<clock::Get>(&()).unwrap();
+    assert!(clock.open);
+  }
+
+  /// Check that we can iterate over a stream's messages.
+  #[test]
+  fn stream_iteration() {
+    let api_info = ApiInfo::from_parts("https://example.com", "key-id", "secret").unwrap();
+    let client = Client::new(api_info).unwrap();
+    let stream = Stream {
+      runtime: &client.runtime,
+      stream: iter([1, 2, 3]),
+    };
+    assert_eq!(stream.collect::<Vec<_>>(), vec![1, 2, 3]);
+  }
+}
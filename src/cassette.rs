@@ -0,0 +1,426 @@
+// Copyright (C) 2024 The apca Developers
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Error as IoError;
+use std::io::ErrorKind;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::from_utf8;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use http::header::ACCEPT_ENCODING;
+use http::HeaderMap;
+use http::HeaderName;
+use http::HeaderValue;
+use http::Request;
+use http::Response;
+use http::StatusCode;
+use http_body_util::BodyExt as _;
+use http_body_util::Full;
+
+use hyper::body::Bytes;
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::from_reader as json_from_reader;
+use serde_json::to_writer_pretty as json_to_writer_pretty;
+
+use crate::client::is_sensitive_header;
+use crate::client::MASKED;
+use crate::transport::HyperTransport;
+use crate::transport::Transport;
+use crate::transport::TransportBody;
+use crate::transport::TransportError;
+use crate::RequestError;
+
+
+/// An HTTP body as stored in a cassette.
+///
+/// Bodies that are valid UTF-8 are stored as strings, to keep
+/// cassettes readable and editable by hand.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(untagged)]
+enum Body {
+  Text(String),
+  Binary(Vec<u8>),
+}
+
+impl From<&Bytes> for Body {
+  fn from(bytes: &Bytes) -> Self {
+    match from_utf8(bytes) {
+      Ok(text) => Self::Text(text.to_string()),
+      Err(..) => Self::Binary(bytes.to_vec()),
+    }
+  }
+}
+
+impl From<Body> for Bytes {
+  fn from(body: Body) -> Self {
+    match body {
+      Body::Text(text) => Bytes::from(text),
+      Body::Binary(bytes) => Bytes::from(bytes),
+    }
+  }
+}
+
+
+/// Convert a header map into a list of name-value pairs, masking
+/// sensitive values.
+fn headers_to_list(headers: &HeaderMap) -> Vec<(String, String)> {
+  headers
+    .iter()
+    .map(|(name, value)| {
+      let value = if is_sensitive_header(name) {
+        &MASKED
+      } else {
+        value
+      };
+      (
+        name.to_string(),
+        String::from_utf8_lossy(value.as_bytes()).into_owned(),
+      )
+    })
+    .collect()
+}
+
+
+/// The key identifying a request: its method, path, and query.
+type Key = (String, String, Option<String>);
+
+
+/// A recorded request.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+struct RecordedRequest {
+  method: String,
+  path: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  query: Option<String>,
+  #[serde(default)]
+  headers: Vec<(String, String)>,
+  body: Body,
+}
+
+impl RecordedRequest {
+  fn new(request: &Request<Full<Bytes>>, body: &Bytes) -> Self {
+    let (method, path, query) = key(request);
+    Self {
+      method,
+      path,
+      query,
+      headers: headers_to_list(request.headers()),
+      body: Body::from(body),
+    }
+  }
+
+  fn key(&self) -> Key {
+    (self.method.clone(), self.path.clone(), self.query.clone())
+  }
+}
+
+
+/// A recorded response.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+struct RecordedResponse {
+  status: u16,
+  #[serde(default)]
+  headers: Vec<(String, String)>,
+  body: Body,
+}
+
+impl RecordedResponse {
+  fn into_response(self) -> Result<Response<TransportBody>, TransportError> {
+    let status = StatusCode::from_u16(self.status)
+      .map_err(|err| RequestError::Io(IoError::new(ErrorKind::InvalidData, err)))?;
+
+    let mut response = Response::new(
+      Full::new(Bytes::from(self.body))
+        .map_err(|infallible| match infallible {})
+        .boxed(),
+    );
+    *response.status_mut() = status;
+
+    for (name, value) in self.headers {
+      let name = HeaderName::try_from(name)
+        .map_err(|err| RequestError::Io(IoError::new(ErrorKind::InvalidData, err)))?;
+      let value = HeaderValue::try_from(value)
+        .map_err(|err| RequestError::Io(IoError::new(ErrorKind::InvalidData, err)))?;
+      let _ = response.headers_mut().append(name, value);
+    }
+    Ok(response)
+  }
+}
+
+
+/// A single request-response pair.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+struct Interaction {
+  request: RecordedRequest,
+  response: RecordedResponse,
+}
+
+
+/// The on-disk representation of a set of interactions.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+struct Cassette {
+  interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+  fn load(path: &Path) -> Result<Self, IoError> {
+    let file = File::open(path)?;
+    let cassette = json_from_reader(BufReader::new(file))?;
+    Ok(cassette)
+  }
+
+  fn save(&self, path: &Path) -> Result<(), IoError> {
+    let file = File::create(path)?;
+    let mut writer = BufWriter::new(file);
+    let () = json_to_writer_pretty(&mut writer, self)?;
+    let () = writer.write_all(b"\n")?;
+    writer.flush()
+  }
+}
+
+
+/// Retrieve the key identifying the provided request.
+fn key<B>(request: &Request<B>) -> Key {
+  let uri = request.uri();
+  (
+    request.method().to_string(),
+    uri.path().to_string(),
+    uri.query().map(str::to_string),
+  )
+}
+
+
+/// A [`Transport`] recording all request-response pairs it sees to a
+/// "cassette" file, for later replay via a [`Replayer`].
+///
+/// Requests are forwarded to an inner transport. Sensitive headers,
+/// i.e., those carrying authentication information, are masked in the
+/// recording. In order to keep recorded bodies human readable, the
+/// recorder does not advertise support for compressed responses to the
+/// server.
+///
+/// The cassette file is (re-)written after every interaction.
+#[derive(Debug)]
+pub struct Recorder<T = HyperTransport> {
+  /// The transport used for actually sending requests.
+  inner: T,
+  /// The path to the cassette file.
+  path: PathBuf,
+  /// The interactions recorded so far.
+  cassette: Mutex<Cassette>,
+}
+
+impl<T> Recorder<T> {
+  /// Create a new `Recorder` forwarding requests to `inner` and
+  /// recording interactions to the file at `path`.
+  ///
+  /// Any existing file at `path` will be overwritten.
+  pub fn new<P>(inner: T, path: P) -> Self
+  where
+    P: Into<PathBuf>,
+  {
+    Self {
+      inner,
+      path: path.into(),
+      cassette: Mutex::new(Cassette::default()),
+    }
+  }
+}
+
+#[async_trait]
+impl<T> Transport for Recorder<T>
+where
+  T: Transport,
+{
+  async fn send(
+    &self,
+    mut request: Request<Full<Bytes>>,
+  ) -> Result<Response<TransportBody>, TransportError> {
+    let _ = request.headers_mut().remove(ACCEPT_ENCODING);
+    // SANITY: Collecting a `Full` body is infallible.
+    let body = request.body().clone().collect().await.unwrap().to_bytes();
+    let recorded = RecordedRequest::new(&request, &body);
+
+    let response = self.inner.send(request).await?;
+    let (parts, body) = response.into_parts();
+    let body = body.collect().await?.to_bytes();
+
+    let interaction = Interaction {
+      request: recorded,
+      response: RecordedResponse {
+        status: parts.status.as_u16(),
+        headers: headers_to_list(&parts.headers),
+        body: Body::from(&body),
+      },
+    };
+
+    {
+      let mut cassette = self.cassette.lock().unwrap();
+      let () = cassette.interactions.push(interaction);
+      let () = cassette.save(&self.path)?;
+    }
+
+    let body = Full::new(body)
+      .map_err(|infallible| match infallible {})
+      .boxed();
+    Ok(Response::from_parts(parts, body))
+  }
+}
+
+
+/// A [`Transport`] serving responses from a "cassette" file previously
+/// created by a [`Recorder`], without accessing the network.
+///
+/// Requests are matched against recorded ones by their method, path,
+/// and query. Multiple interactions matching the same request are
+/// served in the order in which they were recorded, and each is served
+/// only once. A request for which no (more) interactions are available
+/// results in an error.
+#[derive(Debug)]
+pub struct Replayer {
+  /// The responses yet to be served, keyed by request.
+  responses: Mutex<HashMap<Key, VecDeque<RecordedResponse>>>,
+}
+
+impl Replayer {
+  /// Create a `Replayer` serving the interactions recorded in the
+  /// cassette file at `path`.
+  pub fn load<P>(path: P) -> Result<Self, IoError>
+  where
+    P: AsRef<Path>,
+  {
+    let cassette = Cassette::load(path.as_ref())?;
+    let mut responses = HashMap::<_, VecDeque<_>>::new();
+    for Interaction { request, response } in cassette.interactions {
+      let () = responses
+        .entry(request.key())
+        .or_default()
+        .push_back(response);
+    }
+
+    Ok(Self {
+      responses: Mutex::new(responses),
+    })
+  }
+}
+
+#[async_trait]
+impl Transport for Replayer {
+  async fn send(
+    &self,
+    request: Request<Full<Bytes>>,
+  ) -> Result<Response<TransportBody>, TransportError> {
+    let response = self
+      .responses
+      .lock()
+      .unwrap()
+      .get_mut(&key(&request))
+      .and_then(VecDeque::pop_front);
+
+    match response {
+      Some(response) => response.into_response(),
+      None => Err(RequestError::Transport(
+        format!(
+          "no recorded response available for {} {}",
+          request.method(),
+          request.uri()
+        )
+        .into(),
+      )),
+    }
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::env::temp_dir;
+  use std::fs::read_to_string;
+  use std::fs::remove_file;
+
+  use test_log::test;
+
+  use uuid::Uuid;
+
+  use crate::api::v2::clock;
+  use crate::transport::test::response;
+  use crate::transport::test::Canned;
+  use crate::ApiInfo;
+  use crate::Client;
+
+
+  /// Check that we can record interactions and replay them later on.
+  #[test(tokio::test)]
+  async fn record_and_replay() {
+    let path = temp_dir().join(format!("apca-cassette-{}.json", Uuid::new_v4()));
+    let api_info = ApiInfo::from_parts("https://example.com", "key-id", "s3cr3t").unwrap();
+    let body = r#"{
+  "timestamp": "2018-04-01T12:00:00.000Z",
+  "is_open": true,
+  "next_open": "2018-04-01T12:00:00.000Z",
+  "next_close": "2018-04-01T12:00:00.000Z"
+}"#;
+
+    let canned = Canned::new([response(StatusCode::OK, body)]);
+    let recorder = Recorder::new(canned.clone(), &path);
+    let client = Client::builder()
+      .transport(recorder)
+      .build(api_info.clone());
+    let recorded = client.issue::<clock::Get>(&()).await.unwrap();
+
+    // The recorder should not have advertised compression support.
+    {
+      let requests = canned.requests.lock().unwrap();
+      assert_eq!(requests[0].headers().get(ACCEPT_ENCODING), None);
+    }
+
+    let cassette = read_to_string(&path).unwrap();
+    assert!(!cassette.contains("s3cr3t"), "{cassette}");
+    assert!(cassette.contains("<masked>"), "{cassette}");
+
+    let replayer = Replayer::load(&path).unwrap();
+    let () = remove_file(&path).unwrap();
+    let client = Client::builder().transport(replayer).build(api_info);
+    let replayed = client.issue::<clock::Get>(&()).await.unwrap();
+    assert_eq!(replayed, recorded);
+
+    // The interaction was served already and should not be available
+    // anymore.
+    let err = client.issue::<clock::Get>(&()).await.unwrap_err();
+    match err {
+      RequestError::Transport(err) => assert_eq!(
+        err.to_string(),
+        "no recorded response available for GET https://example.com/v2/clock"
+      ),
+      _ => panic!("Received unexpected error: {err:?}"),
+    }
+  }
+
+  /// Check that bodies that are not valid UTF-8 survive a round trip.
+  #[test]
+  fn binary_body() {
+    let bytes = Bytes::from_static(&[0x1f, 0x8b, 0xff, 0x00]);
+    let body = Body::from(&bytes);
+    assert_eq!(body, Body::Binary(bytes.to_vec()));
+
+    let json = serde_json::to_string(&body).unwrap();
+    let body = serde_json::from_str::<Body>(&json).unwrap();
+    assert_eq!(Bytes::from(body), bytes);
+
+    let body = Body::from(&Bytes::from_static(b"{}"));
+    assert_eq!(serde_json::to_string(&body).unwrap(), r#""{}""#);
+  }
+}
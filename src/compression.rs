@@ -0,0 +1,402 @@
+// Copyright (C) 2024 The apca Developers
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::io::Error as IoError;
+use std::io::ErrorKind;
+use std::pin::Pin;
+
+#[cfg(feature = "brotli")]
+use async_compression::futures::bufread::BrotliDecoder;
+#[cfg(feature = "gzip")]
+use async_compression::futures::bufread::GzipDecoder;
+#[cfg(feature = "deflate")]
+use async_compression::futures::bufread::ZlibDecoder;
+#[cfg(feature = "zstd")]
+use async_compression::futures::bufread::ZstdDecoder;
+
+use futures::io::AsyncBufRead;
+#[cfg(any(
+  feature = "brotli",
+  feature = "deflate",
+  feature = "gzip",
+  feature = "zstd"
+))]
+use futures::io::BufReader;
+use futures::AsyncReadExt as _;
+use futures::TryStreamExt as _;
+
+use http::HeaderValue;
+use http_body_util::BodyDataStream;
+
+use hyper::body::Bytes;
+
+use crate::transport::TransportBody;
+use crate::transport::TransportError;
+
+
+/// The content encodings we know about, along with whether support
+/// for them is enabled.
+const ENCODINGS: [(&str, bool); 4] = [
+  ("gzip", cfg!(feature = "gzip")),
+  ("br", cfg!(feature = "brotli")),
+  ("zstd", cfg!(feature = "zstd")),
+  ("deflate", cfg!(feature = "deflate")),
+];
+
+
+/// Retrieve the value of the `Accept-Encoding` header to send, listing
+/// all enabled encodings, if any.
+pub(crate) fn accept_encoding() -> Option<HeaderValue> {
+  let encodings = ENCODINGS
+    .iter()
+    .filter_map(|(encoding, enabled)| enabled.then_some(*encoding))
+    .collect::<Vec<_>>();
+
+  if encodings.is_empty() {
+    None
+  } else {
+    // SANITY: Our encoding names are all valid header values.
+    Some(HeaderValue::from_str(&encodings.join(", ")).unwrap())
+  }
+}
+
+
+/// The type of reader used for (incrementally) decoding response
+/// bodies.
+type Reader = Pin<Box<dyn AsyncBufRead + Send>>;
+
+
+/// Convert a body into a reader yielding its data.
+fn body_reader(body: TransportBody) -> Reader {
+  let stream = BodyDataStream::new(body)
+    .map_err(|err| IoError::new(ErrorKind::Other, err))
+    .into_async_read();
+  Box::pin(stream)
+}
+
+
+/// Wrap a reader into one decoding data using a single content
+/// encoding.
+fn decoder(encoding: &str, reader: Reader) -> Result<Reader, TransportError> {
+  let reader = match encoding {
+    "identity" => reader,
+    #[cfg(feature = "gzip")]
+    "gzip" | "x-gzip" => Box::pin(BufReader::new(GzipDecoder::new(reader))),
+    #[cfg(feature = "brotli")]
+    "br" => Box::pin(BufReader::new(BrotliDecoder::new(reader))),
+    #[cfg(feature = "zstd")]
+    "zstd" => Box::pin(BufReader::new(ZstdDecoder::new(reader))),
+    // Note that the "deflate" content encoding actually refers to
+    // zlib wrapped data.
+    #[cfg(feature = "deflate")]
+    "deflate" => Box::pin(BufReader::new(ZlibDecoder::new(reader))),
+    _ => return Err(TransportError::UnsupportedEncoding(encoding.to_string())),
+  };
+  Ok(reader)
+}
+
+
+/// Recover the error reported by the body from an I/O error, if it
+/// wraps one.
+fn unwrap_io_error(err: IoError) -> TransportError {
+  if err
+    .get_ref()
+    .map(|inner| inner.is::<TransportError>())
+    .unwrap_or(false)
+  {
+    // SANITY: We just checked that the inner error is present and of
+    //         the expected type.
+    *err
+      .into_inner()
+      .unwrap()
+      .downcast::<TransportError>()
+      .unwrap()
+  } else {
+    TransportError::Io(err)
+  }
+}
+
+
+/// Read and decode a response body according to the value of its
+/// `Content-Encoding` header, if any.
+///
+/// Multiple encodings, listed in the order in which they were applied,
+/// are supported. Data is decoded as it is received, without buffering
+/// the encoded body in its entirety. The decoded body, however, is
+/// still collected in full, because endpoints parse their output from
+/// a contiguous buffer. If `limit` is provided, an error is reported
+/// as soon as the decoded body grows larger than that, which is the
+/// only means of bounding memory usage for large responses.
+pub(crate) async fn read_body(
+  encoding: Option<&HeaderValue>,
+  body: TransportBody,
+  limit: Option<usize>,
+) -> Result<Bytes, TransportError> {
+  let mut reader = body_reader(body);
+
+  if let Some(encoding) = encoding {
+    let encoding = encoding
+      .to_str()
+      .map_err(|_| TransportError::UnsupportedEncoding(format!("{encoding:?}")))?;
+
+    for encoding in encoding.rsplit(',') {
+      let encoding = encoding.trim().to_ascii_lowercase();
+      if !encoding.is_empty() {
+        reader = decoder(&encoding, reader)?;
+      }
+    }
+  }
+
+  let mut buffer = Vec::new();
+  match limit {
+    Some(limit) => {
+      // Read at most one byte more than permitted, to be able to tell
+      // whether the limit was exceeded.
+      let max = u64::try_from(limit).unwrap_or(u64::MAX).saturating_add(1);
+      let _count = reader
+        .take(max)
+        .read_to_end(&mut buffer)
+        .await
+        .map_err(unwrap_io_error)?;
+      if buffer.len() > limit {
+        return Err(TransportError::BodyTooLarge(limit))
+      }
+    },
+    None => {
+      let _count = reader
+        .read_to_end(&mut buffer)
+        .await
+        .map_err(unwrap_io_error)?;
+    },
+  }
+  Ok(buffer.into())
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[cfg(any(
+    feature = "brotli",
+    feature = "deflate",
+    feature = "gzip",
+    feature = "zstd"
+  ))]
+  use futures::io::AsyncRead;
+  use futures::stream;
+
+  use http_body_util::BodyExt as _;
+  use http_body_util::StreamBody;
+  use hyper::body::Frame;
+
+  use test_log::test;
+
+
+  /// Check that we advertise exactly the enabled encodings.
+  #[test]
+  fn accept_encoding_header() {
+    let value = accept_encoding();
+    let value = value.as_ref().map(|value| value.to_str().unwrap());
+    assert_eq!(
+      value.is_some(),
+      ENCODINGS.iter().any(|(_, enabled)| *enabled)
+    );
+
+    let value = value.unwrap_or_default();
+    assert_eq!(value.contains("gzip"), cfg!(feature = "gzip"));
+    assert_eq!(value.contains("br"), cfg!(feature = "brotli"));
+    assert_eq!(value.contains("zstd"), cfg!(feature = "zstd"));
+    assert_eq!(value.contains("deflate"), cfg!(feature = "deflate"));
+  }
+
+  /// Create a body for a response from the provided chunks.
+  fn body(chunks: Vec<Bytes>) -> TransportBody {
+    let stream = stream::iter(chunks.into_iter().map(|chunk| Ok(Frame::data(chunk))));
+    StreamBody::new(stream).boxed()
+  }
+
+
+  /// Check that data without or with the identity encoding is passed
+  /// through unchanged.
+  #[test(tokio::test)]
+  async fn decode_identity() {
+    let chunks = vec![Bytes::from_static(b"da"), Bytes::from_static(b"ta")];
+    let decoded = read_body(None, body(chunks.clone()), None).await.unwrap();
+    assert_eq!(decoded, "data");
+
+    let encoding = HeaderValue::from_static("identity");
+    let decoded = read_body(Some(&encoding), body(chunks), None)
+      .await
+      .unwrap();
+    assert_eq!(decoded, "data");
+  }
+
+  /// Check that unknown encodings are reported as errors.
+  #[test(tokio::test)]
+  async fn decode_unsupported() {
+    let encoding = HeaderValue::from_static("compress");
+    let chunks = vec![Bytes::from_static(b"data")];
+    let err = read_body(Some(&encoding), body(chunks), None)
+      .await
+      .unwrap_err();
+    match err {
+      TransportError::UnsupportedEncoding(encoding) => assert_eq!(encoding, "compress"),
+      _ => panic!("Received unexpected error: {err:?}"),
+    }
+  }
+
+  /// Check that we enforce the configured maximum body size.
+  #[test(tokio::test)]
+  async fn body_size_limit() {
+    let chunks = vec![Bytes::from_static(b"da"), Bytes::from_static(b"ta")];
+    let decoded = read_body(None, body(chunks.clone()), Some(4))
+      .await
+      .unwrap();
+    assert_eq!(decoded, "data");
+
+    let err = read_body(None, body(chunks), Some(3)).await.unwrap_err();
+    match err {
+      TransportError::BodyTooLarge(limit) => assert_eq!(limit, 3),
+      _ => panic!("Received unexpected error: {err:?}"),
+    }
+  }
+
+  /// Check that errors reported by the body are passed through.
+  #[test(tokio::test)]
+  async fn body_error() {
+    let stream = stream::iter([
+      Ok(Frame::data(Bytes::from_static(b"da"))),
+      Err(TransportError::Timeout),
+    ]);
+    let body = StreamBody::new(stream).boxed();
+    let err = read_body(None, body, None).await.unwrap_err();
+    assert!(matches!(err, TransportError::Timeout), "{err:?}");
+  }
+
+  /// Read all data from the provided encoder.
+  #[cfg(any(
+    feature = "brotli",
+    feature = "deflate",
+    feature = "gzip",
+    feature = "zstd"
+  ))]
+  async fn encode<E>(mut encoder: E) -> Bytes
+  where
+    E: AsyncRead + Unpin,
+  {
+    let mut buffer = Vec::new();
+    let _count = encoder.read_to_end(&mut buffer).await.unwrap();
+    buffer.into()
+  }
+
+  /// Check that `encoded` decodes to `data` using the given encoding,
+  /// and that the body size limit applies to the decoded data.
+  #[cfg(any(
+    feature = "brotli",
+    feature = "deflate",
+    feature = "gzip",
+    feature = "zstd"
+  ))]
+  async fn check_decode(encoding: &'static str, encoded: Bytes, data: &[u8]) {
+    assert_ne!(encoded, data);
+
+    // Split the encoded data into multiple chunks, to make sure that
+    // we decode incrementally.
+    let chunks = encoded
+      .chunks(7)
+      .map(Bytes::copy_from_slice)
+      .collect::<Vec<_>>();
+    let encoding = HeaderValue::from_static(encoding);
+    let decoded = read_body(Some(&encoding), body(chunks), Some(data.len()))
+      .await
+      .unwrap();
+    assert_eq!(decoded, data);
+
+    // The encoded body fits within the limit, but the decoded one
+    // does not.
+    let limit = data.len() - 1;
+    assert!(encoded.len() <= limit);
+    let err = read_body(Some(&encoding), body(vec![encoded]), Some(limit))
+      .await
+      .unwrap_err();
+    match err {
+      TransportError::BodyTooLarge(size) => assert_eq!(size, limit),
+      _ => panic!("Received unexpected error: {err:?}"),
+    }
+  }
+
+  /// Create data that compresses well.
+  #[cfg(any(
+    feature = "brotli",
+    feature = "deflate",
+    feature = "gzip",
+    feature = "zstd"
+  ))]
+  fn data() -> Bytes {
+    Bytes::from(b"some data that compresses ".repeat(64))
+  }
+
+
+  /// Check that we can decode gzip encoded data.
+  #[cfg(feature = "gzip")]
+  #[test(tokio::test)]
+  async fn decode_gzip() {
+    use async_compression::futures::bufread::GzipEncoder;
+
+    let data = data();
+    let encoded = encode(GzipEncoder::new(&data[..])).await;
+    let () = check_decode("gzip", encoded.clone(), &data).await;
+    let () = check_decode("x-gzip", encoded.clone(), &data).await;
+
+    // Also check that multiple encodings are unwrapped in order.
+    let encoded = encode(GzipEncoder::new(&encoded[..])).await;
+    let () = check_decode("gzip, identity, gzip", encoded, &data).await;
+  }
+
+  /// Check that we can decode brotli encoded data.
+  #[cfg(feature = "brotli")]
+  #[test(tokio::test)]
+  async fn decode_brotli() {
+    use async_compression::futures::bufread::BrotliEncoder;
+
+    let data = data();
+    let encoded = encode(BrotliEncoder::new(&data[..])).await;
+    let () = check_decode("br", encoded, &data).await;
+  }
+
+  /// Check that we can decode zstd encoded data.
+  #[cfg(feature = "zstd")]
+  #[test(tokio::test)]
+  async fn decode_zstd() {
+    use async_compression::futures::bufread::ZstdEncoder;
+
+    let data = data();
+    let encoded = encode(ZstdEncoder::new(&data[..])).await;
+    let () = check_decode("zstd", encoded, &data).await;
+  }
+
+  /// Check that we can decode deflate (i.e., zlib) encoded data.
+  #[cfg(feature = "deflate")]
+  #[test(tokio::test)]
+  async fn decode_deflate() {
+    use async_compression::futures::bufread::ZlibEncoder;
+
+    let data = data();
+    let encoded = encode(ZlibEncoder::new(&data[..])).await;
+    let () = check_decode("deflate", encoded, &data).await;
+  }
+
+  /// Check that data encoded using different encodings can be decoded.
+  #[cfg(all(feature = "brotli", feature = "zstd"))]
+  #[test(tokio::test)]
+  async fn decode_multiple() {
+    use async_compression::futures::bufread::BrotliEncoder;
+    use async_compression::futures::bufread::ZstdEncoder;
+
+    let data = data();
+    let encoded = encode(ZstdEncoder::new(&data[..])).await;
+    let encoded = encode(BrotliEncoder::new(&encoded[..])).await;
+    let () = check_decode("zstd, br", encoded, &data).await;
+  }
+}
@@ -0,0 +1,311 @@
+// Copyright (C) 2024 The apca Developers
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::env::var_os;
+use std::error::Error as StdError;
+use std::fmt::Debug;
+use std::fs::metadata;
+use std::fs::read_to_string;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use crate::secret::Secret;
+use crate::Error;
+use crate::Str;
+
+
+/// The environment variable representing the key ID.
+const ENV_KEY_ID: &str = "APCA_API_KEY_ID";
+/// The environment variable representing the secret key.
+const ENV_SECRET: &str = "APCA_API_SECRET_KEY";
+/// The environment variable representing an OAuth access token.
+const ENV_OAUTH_TOKEN: &str = "APCA_API_OAUTH_TOKEN";
+
+
+/// The credentials used for authenticating with the Alpaca API.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Credentials {
+  /// An API key ID along with the corresponding secret.
+  KeyPair {
+    /// The key ID to use for authentication.
+    key_id: Secret,
+    /// The secret to use for authentication.
+    secret: Secret,
+  },
+  /// An OAuth access token, as used by applications acting on behalf
+  /// of Alpaca users.
+  OAuth {
+    /// The access token to use for authentication.
+    token: Secret,
+  },
+}
+
+impl Credentials {
+  /// Create a `Credentials` object representing the provided API key
+  /// ID and secret.
+  #[inline]
+  pub fn key_pair(key_id: impl ToString, secret: impl ToString) -> Self {
+    Self::KeyPair {
+      key_id: Secret::from(key_id.to_string()),
+      secret: Secret::from(secret.to_string()),
+    }
+  }
+
+  /// Create a `Credentials` object representing the provided OAuth
+  /// access token.
+  #[inline]
+  pub fn oauth(token: impl ToString) -> Self {
+    Self::OAuth {
+      token: Secret::from(token.to_string()),
+    }
+  }
+
+  /// Assemble credentials from named variables, as retrieved by the
+  /// provided function.
+  ///
+  /// An OAuth access token takes precedence over a key ID and secret.
+  fn from_vars<F, M>(mut lookup: F, missing: M) -> Result<Self, Str>
+  where
+    F: FnMut(&str) -> Result<Option<String>, Str>,
+    M: Fn(&str) -> Str,
+  {
+    if let Some(token) = lookup(ENV_OAUTH_TOKEN)? {
+      return Ok(Self::OAuth {
+        token: Secret::from(token),
+      })
+    }
+
+    let key_id = lookup(ENV_KEY_ID)?.ok_or_else(|| missing(ENV_KEY_ID))?;
+    let secret = lookup(ENV_SECRET)?.ok_or_else(|| missing(ENV_SECRET))?;
+    Ok(Self::KeyPair {
+      key_id: Secret::from(key_id),
+      secret: Secret::from(secret),
+    })
+  }
+
+  /// Retrieve credentials from the environment.
+  ///
+  /// The key ID is read from the `APCA_API_KEY_ID` variable and the
+  /// secret from `APCA_API_SECRET_KEY`. Alternatively, an OAuth access
+  /// token is read from `APCA_API_OAUTH_TOKEN`, if set.
+  pub fn from_env() -> Result<Self, Error> {
+    let lookup = |name: &str| {
+      var_os(name)
+        .map(|value| {
+          value
+            .into_string()
+            .map_err(|_| format!("{name} environment variable is not a valid string").into())
+        })
+        .transpose()
+    };
+    let missing = |name: &str| format!("{name} environment variable not found").into();
+
+    Self::from_vars(lookup, missing).map_err(Error::Str)
+  }
+
+  /// Parse credentials from the contents of a file at the given path.
+  fn from_file_contents(path: &Path, contents: &str) -> Result<Self, Str> {
+    let mut vars = Vec::new();
+    for line in contents.lines() {
+      let line = line.trim();
+      if line.is_empty() || line.starts_with('#') {
+        continue
+      }
+
+      let (name, value) = line
+        .split_once('=')
+        .ok_or_else(|| format!("{}: encountered malformed line: {line}", path.display()))?;
+      let () = vars.push((name.trim(), value.trim()));
+    }
+
+    let lookup = |name: &str| {
+      Ok(
+        vars
+          .iter()
+          .find(|(var, _)| *var == name)
+          .map(|(_, value)| value.to_string()),
+      )
+    };
+    let missing = |name: &str| format!("{}: {name} not found", path.display()).into();
+
+    Self::from_vars(lookup, missing)
+  }
+}
+
+
+/// A trait for objects providing the credentials to use for
+/// authenticating with the Alpaca API.
+///
+/// A provider can be registered with a [`Client`][crate::Client] via
+/// [`Client::builder`][crate::Client::builder], in which case it is
+/// consulted for every request issued and for every stream connected
+/// through the client. That allows for credentials to change over the
+/// lifetime of a client, e.g., as part of periodic key rotation.
+pub trait CredentialsProvider: Debug + Send + Sync {
+  /// Retrieve the credentials to use.
+  fn credentials(&self) -> Result<Credentials, Box<dyn StdError + Send + Sync>>;
+}
+
+impl<P> CredentialsProvider for Arc<P>
+where
+  P: CredentialsProvider + ?Sized,
+{
+  #[inline]
+  fn credentials(&self) -> Result<Credentials, Box<dyn StdError + Send + Sync>> {
+    self.as_ref().credentials()
+  }
+}
+
+/// A provider of static credentials.
+impl CredentialsProvider for Credentials {
+  #[inline]
+  fn credentials(&self) -> Result<Credentials, Box<dyn StdError + Send + Sync>> {
+    Ok(self.clone())
+  }
+}
+
+
+/// A provider reading credentials from the environment each time they
+/// are requested.
+///
+/// See [`Credentials::from_env`] for the variables used.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EnvCredentials;
+
+impl CredentialsProvider for EnvCredentials {
+  #[inline]
+  fn credentials(&self) -> Result<Credentials, Box<dyn StdError + Send + Sync>> {
+    let credentials = Credentials::from_env()?;
+    Ok(credentials)
+  }
+}
+
+
+/// A provider reading credentials from a file, re-reading it whenever
+/// it changed.
+///
+/// The file contains lines of `NAME=VALUE` pairs, using the same names
+/// as the environment variables described for
+/// [`Credentials::from_env`]. Empty lines and lines starting with `#`
+/// are ignored.
+#[derive(Debug)]
+pub struct FileCredentials {
+  /// The path to the file containing the credentials.
+  path: PathBuf,
+  /// The most recently read credentials, along with the modification
+  /// time and size of the file at the time they were read.
+  cached: Mutex<Option<((SystemTime, u64), Credentials)>>,
+}
+
+impl FileCredentials {
+  /// Create a `FileCredentials` object reading credentials from the
+  /// file at the given path.
+  pub fn new(path: impl Into<PathBuf>) -> Self {
+    Self {
+      path: path.into(),
+      cached: Mutex::new(None),
+    }
+  }
+}
+
+impl CredentialsProvider for FileCredentials {
+  fn credentials(&self) -> Result<Credentials, Box<dyn StdError + Send + Sync>> {
+    let error = |err| format!("{}: {err}", self.path.display());
+
+    // We consider the file changed if either its modification time or
+    // its size changed.
+    let version = metadata(&self.path)
+      .and_then(|metadata| Ok((metadata.modified()?, metadata.len())))
+      .map_err(error)?;
+
+    let mut cached = self.cached.lock().unwrap();
+    match &*cached {
+      Some((cached_version, credentials)) if *cached_version == version => Ok(credentials.clone()),
+      _ => {
+        let contents = read_to_string(&self.path).map_err(error)?;
+        let credentials = Credentials::from_file_contents(&self.path, &contents)?;
+        *cached = Some((version, credentials.clone()));
+        Ok(credentials)
+      },
+    }
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::env::temp_dir;
+  use std::fs::remove_file;
+  use std::fs::write;
+  use std::process;
+
+
+  /// Check that we can parse credentials from a file's contents.
+  #[test]
+  fn parse_file_contents() {
+    let path = Path::new("credentials");
+    let contents = r#"
+# Our keys.
+APCA_API_KEY_ID = XXXXXXXXXXXXXXXXXXXX
+APCA_API_SECRET_KEY=YYYYYYYYYYYYYYYYYYYY
+"#;
+    let credentials = Credentials::from_file_contents(path, contents).unwrap();
+    assert_eq!(
+      credentials,
+      Credentials::key_pair("XXXXXXXXXXXXXXXXXXXX", "YYYYYYYYYYYYYYYYYYYY")
+    );
+
+    let contents = "APCA_API_KEY_ID=XXX\nAPCA_API_OAUTH_TOKEN=ZZZ\n";
+    let credentials = Credentials::from_file_contents(path, contents).unwrap();
+    assert_eq!(credentials, Credentials::oauth("ZZZ"));
+
+    let contents = "APCA_API_KEY_ID=XXX\n";
+    let err = Credentials::from_file_contents(path, contents).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "credentials: APCA_API_SECRET_KEY not found"
+    );
+
+    let contents = "APCA_API_KEY_ID\n";
+    let err = Credentials::from_file_contents(path, contents).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "credentials: encountered malformed line: APCA_API_KEY_ID"
+    );
+  }
+
+  /// Check that a `FileCredentials` provider picks up changes to the
+  /// file.
+  #[test]
+  fn file_credentials_rotation() {
+    let path = temp_dir().join(format!("apca-credentials-{}", process::id()));
+    let () = write(&path, "APCA_API_OAUTH_TOKEN=first\n").unwrap();
+
+    let provider = FileCredentials::new(&path);
+    assert_eq!(provider.credentials().unwrap(), Credentials::oauth("first"));
+
+    // Note that the file's size changes as well, so that the change is
+    // detected even on file systems with coarse timestamp granularity.
+    let () = write(&path, "APCA_API_OAUTH_TOKEN=second\n").unwrap();
+    assert_eq!(
+      provider.credentials().unwrap(),
+      Credentials::oauth("second")
+    );
+
+    let () = remove_file(&path).unwrap();
+    assert!(provider.credentials().is_err());
+  }
+
+  /// Check that static credentials are provided as-is.
+  #[test]
+  fn static_credentials() {
+    let credentials = Credentials::key_pair("key-id", "secret");
+    assert_eq!(credentials.credentials().unwrap(), credentials);
+  }
+}
@@ -1,10 +1,93 @@
 // Copyright (C) 2020-2022 The apca Developers
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+/// Definitions for the `v1beta3` version of the Alpaca Data API,
+/// covering crypto currency market data.
+pub mod v1beta3;
 /// Definitions for the second version of the Alpaca Data API.
 pub mod v2;
 
+use std::borrow::Cow;
+use std::ptr;
+
+use http_endpoint::Endpoint;
+
+use crate::Str;
+
+
 /// The API base URL used for retrieving market data.
 pub(crate) const DATA_BASE_URL: &str = "https://data.alpaca.markets";
 /// The base URL for streaming market data over a websocket connection.
 pub(crate) const DATA_STREAM_BASE_URL: &str = "wss://stream.data.alpaca.markets";
+
+/// The marker reported as base URL by Data API endpoints.
+///
+/// Data API endpoints are identified by reporting this very string,
+/// not merely an equal one, via [`base_url`]. The client then uses the
+/// configured [`ApiInfo::data_base_url`][crate::ApiInfo::data_base_url]
+/// instead. The marker's value is the default data base URL, so that
+/// endpoints used without our client still report a usable URL.
+static DATA_ENDPOINT_MARKER: &str = DATA_BASE_URL;
+
+
+/// Retrieve the base URL to be reported by Data API endpoints.
+///
+/// All Data API endpoints have to use this function for reporting
+/// their base URL, as that is what marks them as such.
+#[inline]
+pub(crate) fn base_url() -> Option<Str> {
+  Some(Cow::Borrowed(DATA_ENDPOINT_MARKER))
+}
+
+
+/// Check whether an endpoint is a Data API endpoint, i.e., whether it
+/// reports its base URL via [`base_url`].
+pub(crate) fn is_data_endpoint<R>() -> bool
+where
+  R: Endpoint,
+{
+  matches!(R::base_url(), Some(Cow::Borrowed(url)) if ptr::eq(url, DATA_ENDPOINT_MARKER))
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::api::v2::clock;
+
+
+  EndpointNoParse! {
+    /// An endpoint reporting the Data API base URL without using the
+    /// marker.
+    Unmarked(()),
+    Ok => (), [],
+    Err => UnmarkedError, []
+
+    fn base_url() -> Option<Str> {
+      Some(DATA_BASE_URL.to_string().into())
+    }
+
+    fn path(_input: &Self::Input) -> Str {
+      "/".into()
+    }
+
+    fn parse(_body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+      Ok(())
+    }
+
+    fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+      Err(body.to_vec())
+    }
+  }
+
+
+  /// Check that we correctly identify Data API endpoints.
+  #[test]
+  fn data_endpoint() {
+    assert!(is_data_endpoint::<v2::bars::List>());
+    assert!(is_data_endpoint::<v1beta3::crypto::snapshots::Get>());
+    assert!(!is_data_endpoint::<clock::Get>());
+    assert!(!is_data_endpoint::<Unmarked>());
+  }
+}
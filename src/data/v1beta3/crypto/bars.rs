@@ -0,0 +1,290 @@
+// Copyright (C) 2024 The apca Developers
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::collections::BTreeMap;
+
+use chrono::DateTime;
+use chrono::Utc;
+
+use num_decimal::Num;
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_urlencoded::to_string as to_query;
+
+use crate::data;
+use crate::data::v1beta3::crypto::Loc;
+use crate::paginate::Paginated;
+use crate::util::map_from_str;
+use crate::util::string_slice_to_str;
+use crate::Str;
+
+pub use crate::data::v2::bars::TimeFrame;
+
+
+/// A GET request to be issued to the /v1beta3/crypto/{loc}/bars
+/// endpoint.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ListReq {
+  /// The currency pairs for which to retrieve market data, e.g.,
+  /// `BTC/USD`.
+  #[serde(rename = "symbols", serialize_with = "string_slice_to_str")]
+  pub symbols: Vec<String>,
+  /// The location to retrieve market data for.
+  #[serde(skip)]
+  pub loc: Loc,
+  /// The maximum number of bars to be returned in total, across all
+  /// symbols.
+  ///
+  /// It can be between 1 and 10000. Defaults to 1000 if the provided
+  /// value is `None`.
+  #[serde(rename = "limit")]
+  pub limit: Option<usize>,
+  /// Filter bars equal to or after this time.
+  #[serde(rename = "start")]
+  pub start: DateTime<Utc>,
+  /// Filter bars equal to or before this time.
+  #[serde(rename = "end")]
+  pub end: DateTime<Utc>,
+  /// The time frame for the bars.
+  #[serde(rename = "timeframe")]
+  pub timeframe: TimeFrame,
+  /// If provided we will pass a page token to continue where we left off.
+  #[serde(rename = "page_token", skip_serializing_if = "Option::is_none")]
+  pub page_token: Option<String>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  #[serde(skip)]
+  pub _non_exhaustive: (),
+}
+
+
+/// A helper for initializing [`ListReq`] objects.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ListReqInit {
+  /// See `ListReq::loc`.
+  pub loc: Loc,
+  /// See `ListReq::limit`.
+  pub limit: Option<usize>,
+  /// See `ListReq::page_token`.
+  pub page_token: Option<String>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  pub _non_exhaustive: (),
+}
+
+impl ListReqInit {
+  /// Create a [`ListReq`] from a `ListReqInit`.
+  #[inline]
+  pub fn init<I, S>(
+    self,
+    symbols: I,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    timeframe: TimeFrame,
+  ) -> ListReq
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    ListReq {
+      symbols: symbols.into_iter().map(S::into).collect(),
+      loc: self.loc,
+      start,
+      end,
+      timeframe,
+      limit: self.limit,
+      page_token: self.page_token,
+      _non_exhaustive: (),
+    }
+  }
+}
+
+
+/// A crypto currency market data bar as returned by the
+/// /v1beta3/crypto/{loc}/bars endpoint.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+pub struct Bar {
+  /// The beginning time of this bar.
+  #[serde(rename = "t")]
+  pub time: DateTime<Utc>,
+  /// The open price.
+  #[serde(rename = "o")]
+  pub open: Num,
+  /// The close price.
+  #[serde(rename = "c")]
+  pub close: Num,
+  /// The highest price.
+  #[serde(rename = "h")]
+  pub high: Num,
+  /// The lowest price.
+  #[serde(rename = "l")]
+  pub low: Num,
+  /// The trading volume.
+  #[serde(rename = "v")]
+  pub volume: Num,
+  /// The number of trades.
+  #[serde(rename = "n")]
+  pub trade_count: u64,
+  /// The volume weighted average price.
+  #[serde(rename = "vw")]
+  pub weighted_average: Num,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  #[serde(skip)]
+  pub _non_exhaustive: (),
+}
+
+
+/// A collection of bars as returned by the API. This is one page of
+/// bars.
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub struct Bars {
+  /// The returned bars, keyed by symbol.
+  #[serde(rename = "bars", default, deserialize_with = "map_from_str")]
+  pub bars: BTreeMap<String, Vec<Bar>>,
+  /// The token to provide to a request to get the next page of bars
+  /// for this request.
+  #[serde(rename = "next_page_token")]
+  pub next_page_token: Option<String>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  #[serde(skip)]
+  pub _non_exhaustive: (),
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the
+  /// /v1beta3/crypto/{loc}/bars endpoint.
+  pub List(ListReq),
+  Ok => Bars, [
+    /// The market data was retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => ListError, [
+    /// A query parameter was invalid.
+    /* 400 */ BAD_REQUEST => InvalidInput,
+  ]
+
+  fn base_url() -> Option<Str> {
+    data::base_url()
+  }
+
+  fn path(input: &Self::Input) -> Str {
+    format!("/v1beta3/crypto/{}/bars", input.loc.as_ref()).into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    Ok(Some(to_query(input)?.into()))
+  }
+}
+
+/// Pagination spans the entire set of symbols requested. Items are
+/// reported along with the symbol they belong to.
+impl Paginated for List {
+  type Item = (String, Bar);
+
+  #[inline]
+  fn set_page_token(input: &mut Self::Input, token: String) {
+    input.page_token = Some(token);
+  }
+
+  #[inline]
+  fn into_items(output: Self::Output) -> (Vec<Self::Item>, Option<String>) {
+    let items = output
+      .bars
+      .into_iter()
+      .flat_map(|(symbol, bars)| bars.into_iter().map(move |bar| (symbol.clone(), bar)))
+      .collect();
+    (items, output.next_page_token)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::str::FromStr as _;
+
+  use http_endpoint::Endpoint;
+
+  use serde_json::from_str as from_json;
+
+  use test_log::test;
+
+  use crate::api_info::ApiInfo;
+  use crate::Client;
+
+
+  /// Verify that we can properly parse a reference bar response.
+  #[test]
+  fn parse_reference_bars() {
+    let response = r#"{
+  "bars": {
+    "BTC/USD": [
+      {
+        "c": 66120.4,
+        "h": 66146.2,
+        "l": 66101.9,
+        "n": 21,
+        "o": 66101.9,
+        "t": "2024-03-01T00:00:00Z",
+        "v": 0.231856,
+        "vw": 66129.1707316
+      }
+    ]
+  },
+  "next_page_token": null
+}"#;
+
+    let res = from_json::<<List as Endpoint>::Output>(response).unwrap();
+    let bars = &res.bars["BTC/USD"];
+    assert_eq!(bars.len(), 1);
+    assert_eq!(
+      bars[0].time,
+      DateTime::<Utc>::from_str("2024-03-01T00:00:00Z").unwrap()
+    );
+    assert_eq!(bars[0].open, Num::new(661019, 10));
+    assert_eq!(bars[0].volume, Num::new(231856, 1000000));
+    assert_eq!(bars[0].trade_count, 21);
+    assert_eq!(res.next_page_token, None);
+  }
+
+  /// Check that we create the expected request path and query.
+  #[test]
+  fn request_path_and_query() {
+    let start = DateTime::from_str("2024-03-01T00:00:00Z").unwrap();
+    let end = DateTime::from_str("2024-03-02T00:00:00Z").unwrap();
+    let request = ListReqInit {
+      loc: Loc::EU1,
+      ..Default::default()
+    }
+    .init(["BTC/USD", "ETH/USD"], start, end, TimeFrame::Hour(1));
+
+    assert_eq!(List::path(&request), "/v1beta3/crypto/eu-1/bars");
+    let query = List::query(&request).unwrap().unwrap();
+    assert!(
+      query.starts_with("symbols=BTC%2FUSD%2CETH%2FUSD&"),
+      "{query}"
+    );
+    assert!(query.contains("timeframe=1Hour"), "{query}");
+  }
+
+  /// Check that we can request historic bar data for crypto currency
+  /// pairs.
+  #[test(tokio::test)]
+  async fn request_bars() {
+    let api_info = ApiInfo::from_env().unwrap();
+    let client = Client::new(api_info);
+    let start = DateTime::from_str("2024-03-01T00:00:00Z").unwrap();
+    let end = DateTime::from_str("2024-03-03T00:00:00Z").unwrap();
+    let request = ListReqInit::default().init(["BTC/USD"], start, end, TimeFrame::OneDay);
+
+    let res = client.issue::<List>(&request).await.unwrap();
+    let bars = &res.bars["BTC/USD"];
+    assert_eq!(bars.len(), 2);
+    assert!(bars[0].open > Num::from(10000));
+  }
+}
@@ -0,0 +1,188 @@
+// Copyright (C) 2024 The apca Developers
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::from_slice as from_json;
+use serde_urlencoded::to_string as to_query;
+
+use crate::data;
+use crate::data::v1beta3::crypto::Loc;
+use crate::util::string_slice_to_str;
+use crate::Str;
+
+pub use crate::data::v1beta3::crypto::quotes::Quote;
+
+
+/// A GET request to be made to the
+/// /v1beta3/crypto/{loc}/latest/quotes endpoint.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct GetReq {
+  /// The currency pairs to retrieve the last quote for, e.g.,
+  /// `BTC/USD`.
+  #[serde(rename = "symbols", serialize_with = "string_slice_to_str")]
+  pub symbols: Vec<String>,
+  /// The location to retrieve market data for.
+  #[serde(skip)]
+  pub loc: Loc,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  #[serde(skip)]
+  pub _non_exhaustive: (),
+}
+
+
+/// A helper for initializing [`GetReq`] objects.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[allow(missing_copy_implementations)]
+pub struct GetReqInit {
+  /// See `GetReq::loc`.
+  pub loc: Loc,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  pub _non_exhaustive: (),
+}
+
+impl GetReqInit {
+  /// Create a [`GetReq`] from a `GetReqInit`.
+  #[inline]
+  pub fn init<I, S>(self, symbols: I) -> GetReq
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    GetReq {
+      symbols: symbols.into_iter().map(S::into).collect(),
+      loc: self.loc,
+      _non_exhaustive: (),
+    }
+  }
+}
+
+
+EndpointNoParse! {
+  /// The representation of a GET request to the
+  /// /v1beta3/crypto/{loc}/latest/quotes endpoint.
+  pub Get(GetReq),
+  Ok => Vec<(String, Quote)>, [
+    /// The last quotes were retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => GetError, [
+    /// The provided symbol was invalid or not found.
+    /* 400 */ BAD_REQUEST => InvalidInput,
+  ]
+
+  fn base_url() -> Option<Str> {
+    data::base_url()
+  }
+
+  fn path(input: &Self::Input) -> Str {
+    format!("/v1beta3/crypto/{}/latest/quotes", input.loc.as_ref()).into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    Ok(Some(to_query(input)?.into()))
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    /// A helper object for parsing the response to a `Get` request.
+    #[derive(Deserialize)]
+    struct Response {
+      /// A mapping from symbols to quote objects.
+      // We use a `BTreeMap` here to have a consistent ordering of
+      // quotes.
+      quotes: BTreeMap<String, Quote>,
+    }
+
+    from_json::<Response>(body)
+      .map(|response| response.quotes.into_iter().collect())
+      .map_err(Self::ConversionError::from)
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    from_json::<Self::ApiError>(body).map_err(|_| body.to_vec())
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use chrono::DateTime;
+  use chrono::Duration;
+  use chrono::Utc;
+
+  use http_endpoint::Endpoint as _;
+
+  use num_decimal::Num;
+
+  use test_log::test;
+
+  use crate::api_info::ApiInfo;
+  use crate::Client;
+
+
+  /// Check that we can parse a reference latest quotes response.
+  #[test]
+  fn parse_reference_quotes() {
+    let response = br#"{
+  "quotes": {
+    "ETH/USD": {
+      "ap": 3457.5,
+      "as": 4.31,
+      "bp": 3455.93,
+      "bs": 4.3,
+      "t": "2024-03-14T10:14:58.517397541Z"
+    },
+    "BTC/USD": {
+      "ap": 72871.9,
+      "as": 0.27,
+      "bp": 72826.51,
+      "bs": 0.28,
+      "t": "2024-03-14T10:14:58.517397541Z"
+    }
+  }
+}"#;
+
+    let quotes = Get::parse(response).unwrap();
+    assert_eq!(quotes.len(), 2);
+    assert_eq!(quotes[0].0, "BTC/USD");
+    assert_eq!(quotes[0].1.ask_price, Num::new(728719, 10));
+    assert_eq!(quotes[0].1.bid_size, Num::new(28, 100));
+    assert_eq!(quotes[1].0, "ETH/USD");
+    assert_eq!(
+      quotes[1].1.time,
+      DateTime::parse_from_rfc3339("2024-03-14T10:14:58.517397541Z").unwrap()
+    );
+  }
+
+  /// Check that the location is reflected in the request path.
+  #[test]
+  fn request_path() {
+    let req = GetReqInit {
+      loc: Loc::US1,
+      ..Default::default()
+    }
+    .init(["BTC/USD"]);
+    assert_eq!(Get::path(&req), "/v1beta3/crypto/us-1/latest/quotes");
+  }
+
+  /// Verify that we can retrieve the latest quotes for multiple
+  /// currency pairs.
+  #[test(tokio::test)]
+  async fn request_last_quotes() {
+    let api_info = ApiInfo::from_env().unwrap();
+    let client = Client::new(api_info);
+
+    let req = GetReqInit::default().init(["BTC/USD", "ETH/USD"]);
+    let quotes = client.issue::<Get>(&req).await.unwrap();
+    assert_eq!(quotes.len(), 2);
+    assert_eq!(quotes[0].0, "BTC/USD");
+    assert!(quotes[0].1.time >= Utc::now() - Duration::try_days(1).unwrap());
+    assert_eq!(quotes[1].0, "ETH/USD");
+  }
+}
@@ -0,0 +1,39 @@
+// Copyright (C) 2024 The apca Developers
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+/// Definitions for retrieval of crypto currency bars.
+pub mod bars;
+/// Functionality for retrieval of most recent crypto currency quotes.
+pub mod last_quotes;
+/// Functionality for retrieving historic crypto currency quotes.
+pub mod quotes;
+/// Functionality for retrieval of crypto currency market snapshots.
+pub mod snapshots;
+/// Definitions for retrieval of crypto currency trades.
+pub mod trades;
+
+
+/// An enumeration of the locations, i.e., groups of exchanges, for
+/// which crypto currency market data is available.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum Loc {
+  /// Alpaca US.
+  #[default]
+  US,
+  /// Kraken US.
+  US1,
+  /// Kraken EU.
+  EU1,
+}
+
+impl AsRef<str> for Loc {
+  #[inline]
+  fn as_ref(&self) -> &'static str {
+    match *self {
+      Loc::US => "us",
+      Loc::US1 => "us-1",
+      Loc::EU1 => "eu-1",
+    }
+  }
+}
@@ -0,0 +1,257 @@
+// Copyright (C) 2024 The apca Developers
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::collections::BTreeMap;
+
+use chrono::DateTime;
+use chrono::Utc;
+
+use num_decimal::Num;
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_urlencoded::to_string as to_query;
+
+use crate::data;
+use crate::data::v1beta3::crypto::Loc;
+use crate::paginate::Paginated;
+use crate::util::map_from_str;
+use crate::util::string_slice_to_str;
+use crate::Str;
+
+/// A GET request to be issued to the /v1beta3/crypto/{loc}/quotes
+/// endpoint.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ListReq {
+  /// The currency pairs for which to retrieve market data, e.g.,
+  /// `BTC/USD`.
+  #[serde(rename = "symbols", serialize_with = "string_slice_to_str")]
+  pub symbols: Vec<String>,
+  /// The location to retrieve market data for.
+  #[serde(skip)]
+  pub loc: Loc,
+  /// The maximum number of quotes to be returned in total, across all
+  /// symbols.
+  ///
+  /// It can be between 1 and 10000. Defaults to 1000 if the provided
+  /// value is `None`.
+  #[serde(rename = "limit")]
+  pub limit: Option<usize>,
+  /// Filter quotes equal to or after this time.
+  #[serde(rename = "start")]
+  pub start: DateTime<Utc>,
+  /// Filter quotes equal to or before this time.
+  #[serde(rename = "end")]
+  pub end: DateTime<Utc>,
+  /// If provided we will pass a page token to continue where we left off.
+  #[serde(rename = "page_token", skip_serializing_if = "Option::is_none")]
+  pub page_token: Option<String>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  #[serde(skip)]
+  pub _non_exhaustive: (),
+}
+
+
+/// A helper for initializing [`ListReq`] objects.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ListReqInit {
+  /// See `ListReq::loc`.
+  pub loc: Loc,
+  /// See `ListReq::limit`.
+  pub limit: Option<usize>,
+  /// See `ListReq::page_token`.
+  pub page_token: Option<String>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  pub _non_exhaustive: (),
+}
+
+impl ListReqInit {
+  /// Create a [`ListReq`] from a `ListReqInit`.
+  #[inline]
+  pub fn init<I, S>(self, symbols: I, start: DateTime<Utc>, end: DateTime<Utc>) -> ListReq
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    ListReq {
+      symbols: symbols.into_iter().map(S::into).collect(),
+      loc: self.loc,
+      start,
+      end,
+      limit: self.limit,
+      page_token: self.page_token,
+      _non_exhaustive: (),
+    }
+  }
+}
+
+
+/// A crypto currency quote as returned by the
+/// /v1beta3/crypto/{loc}/quotes endpoint.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+pub struct Quote {
+  /// The time stamp of this quote.
+  #[serde(rename = "t")]
+  pub time: DateTime<Utc>,
+  /// The ask price.
+  #[serde(rename = "ap")]
+  pub ask_price: Num,
+  /// The ask size.
+  #[serde(rename = "as")]
+  pub ask_size: Num,
+  /// The bid price.
+  #[serde(rename = "bp")]
+  pub bid_price: Num,
+  /// The bid size.
+  #[serde(rename = "bs")]
+  pub bid_size: Num,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  #[serde(skip)]
+  pub _non_exhaustive: (),
+}
+
+
+/// A collection of quotes as returned by the API. This is one page of
+/// quotes.
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub struct Quotes {
+  /// The returned quotes, keyed by symbol.
+  #[serde(rename = "quotes", default, deserialize_with = "map_from_str")]
+  pub quotes: BTreeMap<String, Vec<Quote>>,
+  /// The token to provide to a request to get the next page of quotes
+  /// for this request.
+  #[serde(rename = "next_page_token")]
+  pub next_page_token: Option<String>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  #[serde(skip)]
+  pub _non_exhaustive: (),
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the
+  /// /v1beta3/crypto/{loc}/quotes endpoint.
+  pub List(ListReq),
+  Ok => Quotes, [
+    /// The market data was retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => ListError, [
+    /// A query parameter was invalid.
+    /* 400 */ BAD_REQUEST => InvalidInput,
+  ]
+
+  fn base_url() -> Option<Str> {
+    data::base_url()
+  }
+
+  fn path(input: &Self::Input) -> Str {
+    format!("/v1beta3/crypto/{}/quotes", input.loc.as_ref()).into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    Ok(Some(to_query(input)?.into()))
+  }
+}
+
+/// Pagination spans the entire set of symbols requested. Items are
+/// reported along with the symbol they belong to.
+impl Paginated for List {
+  type Item = (String, Quote);
+
+  #[inline]
+  fn set_page_token(input: &mut Self::Input, token: String) {
+    input.page_token = Some(token);
+  }
+
+  #[inline]
+  fn into_items(output: Self::Output) -> (Vec<Self::Item>, Option<String>) {
+    let items = output
+      .quotes
+      .into_iter()
+      .flat_map(|(symbol, quotes)| quotes.into_iter().map(move |quote| (symbol.clone(), quote)))
+      .collect();
+    (items, output.next_page_token)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::str::FromStr as _;
+
+  use http_endpoint::Endpoint;
+
+  use serde_json::from_str as from_json;
+
+  use test_log::test;
+
+  use crate::api_info::ApiInfo;
+  use crate::Client;
+
+
+  /// Verify that we can properly parse a reference quote response.
+  #[test]
+  fn parse_reference_quotes() {
+    let response = r#"{
+  "next_page_token": null,
+  "quotes": {
+    "BTC/USD": [
+      {
+        "ap": 61210.3,
+        "as": 1.6,
+        "bp": 61151.17,
+        "bs": 0.81,
+        "t": "2024-03-01T00:00:00.004925Z"
+      }
+    ]
+  }
+}"#;
+
+    let res = from_json::<<List as Endpoint>::Output>(response).unwrap();
+    let quotes = &res.quotes["BTC/USD"];
+    assert_eq!(quotes.len(), 1);
+    assert_eq!(
+      quotes[0].time,
+      DateTime::<Utc>::from_str("2024-03-01T00:00:00.004925Z").unwrap()
+    );
+    assert_eq!(quotes[0].ask_price, Num::new(612103, 10));
+    assert_eq!(quotes[0].ask_size, Num::new(16, 10));
+    assert_eq!(quotes[0].bid_price, Num::new(6115117, 100));
+    assert_eq!(quotes[0].bid_size, Num::new(81, 100));
+    assert_eq!(res.next_page_token, None);
+  }
+
+  /// Check that a response without any quotes is parsed as empty.
+  #[test]
+  fn parse_empty_quotes() {
+    let response = r#"{"next_page_token": null, "quotes": {}}"#;
+    let res = from_json::<<List as Endpoint>::Output>(response).unwrap();
+    assert!(res.quotes.is_empty());
+  }
+
+  /// Check that we can request historic quote data for crypto currency
+  /// pairs.
+  #[test(tokio::test)]
+  async fn request_quotes() {
+    let api_info = ApiInfo::from_env().unwrap();
+    let client = Client::new(api_info);
+    let start = DateTime::from_str("2024-03-01T00:00:00Z").unwrap();
+    let end = DateTime::from_str("2024-03-01T00:01:00Z").unwrap();
+    let request = ListReqInit {
+      limit: Some(2),
+      ..Default::default()
+    }
+    .init(["BTC/USD", "ETH/USD"], start, end);
+
+    let res = client.issue::<List>(&request).await.unwrap();
+    let quotes = &res.quotes["BTC/USD"];
+    assert_eq!(quotes.len(), 2);
+  }
+}
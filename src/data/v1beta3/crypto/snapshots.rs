@@ -0,0 +1,254 @@
+// Copyright (C) 2024 The apca Developers
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::from_slice as from_json;
+use serde_urlencoded::to_string as to_query;
+
+use crate::data;
+use crate::data::v1beta3::crypto::bars::Bar;
+use crate::data::v1beta3::crypto::quotes::Quote;
+use crate::data::v1beta3::crypto::trades::Trade;
+use crate::data::v1beta3::crypto::Loc;
+use crate::util::string_slice_to_str;
+use crate::Str;
+
+
+/// A GET request to be made to the /v1beta3/crypto/{loc}/snapshots
+/// endpoint.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct GetReq {
+  /// The currency pairs to retrieve snapshots for, e.g., `BTC/USD`.
+  #[serde(rename = "symbols", serialize_with = "string_slice_to_str")]
+  pub symbols: Vec<String>,
+  /// The location to retrieve market data for.
+  #[serde(skip)]
+  pub loc: Loc,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  #[serde(skip)]
+  pub _non_exhaustive: (),
+}
+
+
+/// A helper for initializing [`GetReq`] objects.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[allow(missing_copy_implementations)]
+pub struct GetReqInit {
+  /// See `GetReq::loc`.
+  pub loc: Loc,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  pub _non_exhaustive: (),
+}
+
+impl GetReqInit {
+  /// Create a [`GetReq`] from a `GetReqInit`.
+  #[inline]
+  pub fn init<I, S>(self, symbols: I) -> GetReq
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    GetReq {
+      symbols: symbols.into_iter().map(S::into).collect(),
+      loc: self.loc,
+      _non_exhaustive: (),
+    }
+  }
+}
+
+
+/// A snapshot of the current market state of a currency pair, as
+/// returned by the /v1beta3/crypto/{loc}/snapshots endpoint.
+///
+/// Any of the members may be absent.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+pub struct Snapshot {
+  /// The latest trade.
+  #[serde(rename = "latestTrade")]
+  pub latest_trade: Option<Trade>,
+  /// The latest quote.
+  #[serde(rename = "latestQuote")]
+  pub latest_quote: Option<Quote>,
+  /// The current minute bar.
+  #[serde(rename = "minuteBar")]
+  pub minute_bar: Option<Bar>,
+  /// The current daily bar.
+  #[serde(rename = "dailyBar")]
+  pub daily_bar: Option<Bar>,
+  /// The previous daily bar.
+  #[serde(rename = "prevDailyBar")]
+  pub prev_daily_bar: Option<Bar>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  #[serde(skip)]
+  pub _non_exhaustive: (),
+}
+
+
+EndpointNoParse! {
+  /// The representation of a GET request to the
+  /// /v1beta3/crypto/{loc}/snapshots endpoint.
+  pub Get(GetReq),
+  Ok => Vec<(String, Snapshot)>, [
+    /// The snapshots were retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => GetError, [
+    /// The provided symbol was invalid or not found.
+    /* 400 */ BAD_REQUEST => InvalidInput,
+  ]
+
+  fn base_url() -> Option<Str> {
+    data::base_url()
+  }
+
+  fn path(input: &Self::Input) -> Str {
+    format!("/v1beta3/crypto/{}/snapshots", input.loc.as_ref()).into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    Ok(Some(to_query(input)?.into()))
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    /// A helper object for parsing the response to a `Get` request.
+    #[derive(Deserialize)]
+    struct Response {
+      /// A mapping from symbols to snapshot objects.
+      // We use a `BTreeMap` here to have a consistent ordering of
+      // snapshots.
+      snapshots: BTreeMap<String, Option<Snapshot>>,
+    }
+
+    // Symbols without any data are reported as `null`, and we skip
+    // those.
+    from_json::<Response>(body)
+      .map(|response| {
+        response
+          .snapshots
+          .into_iter()
+          .filter_map(|(symbol, snapshot)| snapshot.map(|snapshot| (symbol, snapshot)))
+          .collect()
+      })
+      .map_err(Self::ConversionError::from)
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    from_json::<Self::ApiError>(body).map_err(|_| body.to_vec())
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use chrono::DateTime;
+
+  use http_endpoint::Endpoint as _;
+
+  use num_decimal::Num;
+
+  use test_log::test;
+
+  use crate::api_info::ApiInfo;
+  use crate::Client;
+
+
+  /// Check that we can parse a reference snapshots response.
+  #[test]
+  fn parse_reference_snapshots() {
+    let response = br#"{
+  "snapshots": {
+    "BTC/USD": {
+      "dailyBar": {
+        "c": 72839.3,
+        "h": 73087.7,
+        "l": 72550.1,
+        "n": 236,
+        "o": 72951.4,
+        "t": "2024-03-14T05:00:00Z",
+        "v": 2.34958,
+        "vw": 72876.1
+      },
+      "latestQuote": {
+        "ap": 72871.9,
+        "as": 0.27,
+        "bp": 72826.51,
+        "bs": 0.28,
+        "t": "2024-03-14T10:14:58.517397541Z"
+      },
+      "latestTrade": {
+        "i": 3498195716,
+        "p": 72839.3,
+        "s": 0.0125,
+        "t": "2024-03-14T10:14:41.44712Z",
+        "tks": "B"
+      },
+      "minuteBar": {
+        "c": 72839.3,
+        "h": 72839.3,
+        "l": 72839.3,
+        "n": 1,
+        "o": 72839.3,
+        "t": "2024-03-14T10:14:00Z",
+        "v": 0.0125,
+        "vw": 72839.3
+      },
+      "prevDailyBar": {
+        "c": 72951.4,
+        "h": 73650,
+        "l": 70711.1,
+        "n": 2133,
+        "o": 71453.6,
+        "t": "2024-03-13T05:00:00Z",
+        "v": 31.1,
+        "vw": 72312.4
+      }
+    },
+    "FOO/USD": null
+  }
+}"#;
+
+    let snapshots = Get::parse(response).unwrap();
+    assert_eq!(snapshots.len(), 1);
+    assert_eq!(snapshots[0].0, "BTC/USD");
+    let btc = &snapshots[0].1;
+    let trade = btc.latest_trade.as_ref().unwrap();
+    assert_eq!(
+      trade.timestamp,
+      DateTime::parse_from_rfc3339("2024-03-14T10:14:41.44712Z").unwrap()
+    );
+    assert_eq!(trade.size, Num::new(125, 10000));
+    assert_eq!(
+      btc.latest_quote.as_ref().unwrap().ask_price,
+      Num::new(728719, 10)
+    );
+    assert_eq!(btc.minute_bar.as_ref().unwrap().trade_count, 1);
+    assert_eq!(
+      btc.daily_bar.as_ref().unwrap().volume,
+      Num::new(234958, 100000)
+    );
+    assert_eq!(btc.prev_daily_bar.as_ref().unwrap().high, Num::from(73650));
+  }
+
+  /// Verify that we can retrieve snapshots for multiple currency
+  /// pairs.
+  #[test(tokio::test)]
+  async fn request_snapshots() {
+    let api_info = ApiInfo::from_env().unwrap();
+    let client = Client::new(api_info);
+
+    let req = GetReqInit::default().init(["ETH/USD", "BTC/USD"]);
+    let snapshots = client.issue::<Get>(&req).await.unwrap();
+    assert_eq!(snapshots.len(), 2);
+    assert_eq!(snapshots[0].0, "BTC/USD");
+    assert_eq!(snapshots[1].0, "ETH/USD");
+    assert!(snapshots[0].1.daily_bar.is_some());
+  }
+}
@@ -0,0 +1,263 @@
+// Copyright (C) 2024 The apca Developers
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::collections::BTreeMap;
+
+use chrono::DateTime;
+use chrono::Utc;
+
+use num_decimal::Num;
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_urlencoded::to_string as to_query;
+
+use crate::data;
+use crate::data::v1beta3::crypto::Loc;
+use crate::paginate::Paginated;
+use crate::util::map_from_str;
+use crate::util::string_slice_to_str;
+use crate::Str;
+
+/// A GET request to be issued to the /v1beta3/crypto/{loc}/trades
+/// endpoint.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ListReq {
+  /// The currency pairs for which to retrieve market data, e.g.,
+  /// `BTC/USD`.
+  #[serde(rename = "symbols", serialize_with = "string_slice_to_str")]
+  pub symbols: Vec<String>,
+  /// The location to retrieve market data for.
+  #[serde(skip)]
+  pub loc: Loc,
+  /// The maximum number of trades to be returned in total, across all
+  /// symbols.
+  ///
+  /// It can be between 1 and 10000. Defaults to 1000 if the provided
+  /// value is `None`.
+  #[serde(rename = "limit")]
+  pub limit: Option<usize>,
+  /// Filter trades equal to or after this time.
+  #[serde(rename = "start")]
+  pub start: DateTime<Utc>,
+  /// Filter trades equal to or before this time.
+  #[serde(rename = "end")]
+  pub end: DateTime<Utc>,
+  /// If provided we will pass a page token to continue where we left off.
+  #[serde(rename = "page_token", skip_serializing_if = "Option::is_none")]
+  pub page_token: Option<String>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  #[serde(skip)]
+  pub _non_exhaustive: (),
+}
+
+
+/// A helper for initializing [`ListReq`] objects.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ListReqInit {
+  /// See `ListReq::loc`.
+  pub loc: Loc,
+  /// See `ListReq::limit`.
+  pub limit: Option<usize>,
+  /// See `ListReq::page_token`.
+  pub page_token: Option<String>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  pub _non_exhaustive: (),
+}
+
+impl ListReqInit {
+  /// Create a [`ListReq`] from a `ListReqInit`.
+  #[inline]
+  pub fn init<I, S>(self, symbols: I, start: DateTime<Utc>, end: DateTime<Utc>) -> ListReq
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    ListReq {
+      symbols: symbols.into_iter().map(S::into).collect(),
+      loc: self.loc,
+      start,
+      end,
+      limit: self.limit,
+      page_token: self.page_token,
+      _non_exhaustive: (),
+    }
+  }
+}
+
+
+/// A crypto currency trade as returned by the
+/// /v1beta3/crypto/{loc}/trades endpoint.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+pub struct Trade {
+  /// The time stamp of this trade.
+  #[serde(rename = "t")]
+  pub timestamp: DateTime<Utc>,
+  /// The price of the trade.
+  #[serde(rename = "p")]
+  pub price: Num,
+  /// The size of the trade.
+  #[serde(rename = "s")]
+  pub size: Num,
+  /// The taker's side of the trade, if known.
+  #[serde(rename = "tks")]
+  pub taker_side: Option<String>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  #[serde(skip)]
+  pub _non_exhaustive: (),
+}
+
+
+/// A collection of trades as returned by the API. This is one page of
+/// trades.
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub struct Trades {
+  /// The returned trades, keyed by symbol.
+  #[serde(rename = "trades", default, deserialize_with = "map_from_str")]
+  pub trades: BTreeMap<String, Vec<Trade>>,
+  /// The token to provide to a request to get the next page of trades
+  /// for this request.
+  #[serde(rename = "next_page_token")]
+  pub next_page_token: Option<String>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  #[serde(skip)]
+  pub _non_exhaustive: (),
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the
+  /// /v1beta3/crypto/{loc}/trades endpoint.
+  pub List(ListReq),
+  Ok => Trades, [
+    /// The market data was retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => ListError, [
+    /// A query parameter was invalid.
+    /* 400 */ BAD_REQUEST => InvalidInput,
+  ]
+
+  fn base_url() -> Option<Str> {
+    data::base_url()
+  }
+
+  fn path(input: &Self::Input) -> Str {
+    format!("/v1beta3/crypto/{}/trades", input.loc.as_ref()).into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    Ok(Some(to_query(input)?.into()))
+  }
+}
+
+/// Pagination spans the entire set of symbols requested. Items are
+/// reported along with the symbol they belong to.
+impl Paginated for List {
+  type Item = (String, Trade);
+
+  #[inline]
+  fn set_page_token(input: &mut Self::Input, token: String) {
+    input.page_token = Some(token);
+  }
+
+  #[inline]
+  fn into_items(output: Self::Output) -> (Vec<Self::Item>, Option<String>) {
+    let items = output
+      .trades
+      .into_iter()
+      .flat_map(|(symbol, trades)| trades.into_iter().map(move |trade| (symbol.clone(), trade)))
+      .collect();
+    (items, output.next_page_token)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::str::FromStr as _;
+
+  use http_endpoint::Endpoint;
+
+  use serde_json::from_str as from_json;
+
+  use test_log::test;
+
+  use crate::api_info::ApiInfo;
+  use crate::Client;
+
+
+  /// Verify that we can properly parse a reference trade response.
+  #[test]
+  fn parse_reference_trades() {
+    let response = r#"{
+  "next_page_token": "QlRDL1VTRHwyMDI0LTAzLTAxVDAwOjAwOjAwLjIxNjY0NDAwMFp8MTY4NzcwNzE=",
+  "trades": {
+    "BTC/USD": [
+      {
+        "i": 16877070,
+        "p": 61178.8,
+        "s": 0.00034,
+        "t": "2024-03-01T00:00:00.216644Z",
+        "tks": "S"
+      }
+    ]
+  }
+}"#;
+
+    let res = from_json::<<List as Endpoint>::Output>(response).unwrap();
+    let trades = &res.trades["BTC/USD"];
+    assert_eq!(trades.len(), 1);
+    assert_eq!(
+      trades[0].timestamp,
+      DateTime::<Utc>::from_str("2024-03-01T00:00:00.216644Z").unwrap()
+    );
+    assert_eq!(trades[0].price, Num::new(611788, 10));
+    assert_eq!(trades[0].size, Num::new(34, 100000));
+    assert_eq!(trades[0].taker_side.as_deref(), Some("S"));
+    assert!(res.next_page_token.is_some());
+  }
+
+  /// Check that we create the expected request path and query.
+  #[test]
+  fn request_path_and_query() {
+    let start = DateTime::from_str("2024-03-01T00:00:00Z").unwrap();
+    let end = DateTime::from_str("2024-03-02T00:00:00Z").unwrap();
+    let request = ListReqInit {
+      loc: Loc::US1,
+      limit: Some(5),
+      ..Default::default()
+    }
+    .init(["BTC/USD"], start, end);
+
+    assert_eq!(List::path(&request), "/v1beta3/crypto/us-1/trades");
+    let query = List::query(&request).unwrap().unwrap();
+    assert!(query.starts_with("symbols=BTC%2FUSD&limit=5&"), "{query}");
+  }
+
+  /// Check that we can request historic trade data for crypto currency
+  /// pairs.
+  #[test(tokio::test)]
+  async fn request_trades() {
+    let api_info = ApiInfo::from_env().unwrap();
+    let client = Client::new(api_info);
+    let start = DateTime::from_str("2024-03-01T00:00:00Z").unwrap();
+    let end = DateTime::from_str("2024-03-01T00:01:00Z").unwrap();
+    let request = ListReqInit {
+      limit: Some(2),
+      ..Default::default()
+    }
+    .init(["BTC/USD"], start, end);
+
+    let res = client.issue::<List>(&request).await.unwrap();
+    let trades = &res.trades["BTC/USD"];
+    assert_eq!(trades.len(), 2);
+    assert!(res.next_page_token.is_some());
+  }
+}
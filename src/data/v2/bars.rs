@@ -1,6 +1,8 @@
 // Copyright (C) 2021-2024 The apca Developers
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use std::collections::BTreeMap;
+
 use chrono::DateTime;
 use chrono::Utc;
 
@@ -10,8 +12,11 @@ use serde::Deserialize;
 use serde::Serialize;
 use serde_urlencoded::to_string as to_query;
 
+use crate::data;
 use crate::data::v2::Feed;
-use crate::data::DATA_BASE_URL;
+use crate::paginate::Paginated;
+use crate::util::map_from_str;
+use crate::util::string_slice_to_str;
 use crate::util::vec_from_str;
 use crate::Str;
 
@@ -210,7 +215,7 @@ Endpoint! {
   ]
 
   fn base_url() -> Option<Str> {
-    Some(DATA_BASE_URL.into())
+    data::base_url()
   }
 
   fn path(input: &Self::Input) -> Str {
@@ -222,6 +227,173 @@ Endpoint! {
   }
 }
 
+impl Paginated for List {
+  type Item = Bar;
+
+  #[inline]
+  fn set_page_token(input: &mut Self::Input, token: String) {
+    input.page_token = Some(token);
+  }
+
+  #[inline]
+  fn into_items(output: Self::Output) -> (Vec<Self::Item>, Option<String>) {
+    (output.bars, output.next_page_token)
+  }
+}
+
+
+/// A GET request to be issued to the /v2/stocks/bars endpoint,
+/// retrieving bars for multiple symbols at once.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ListMultiReq {
+  /// The symbols for which to retrieve market data.
+  #[serde(rename = "symbols", serialize_with = "string_slice_to_str")]
+  pub symbols: Vec<String>,
+  /// The maximum number of bars to be returned in total, across all
+  /// symbols.
+  ///
+  /// It can be between 1 and 10000. Defaults to 1000 if the provided
+  /// value is `None`.
+  #[serde(rename = "limit")]
+  pub limit: Option<usize>,
+  /// Filter bars equal to or after this time.
+  #[serde(rename = "start")]
+  pub start: DateTime<Utc>,
+  /// Filter bars equal to or before this time.
+  #[serde(rename = "end")]
+  pub end: DateTime<Utc>,
+  /// The time frame for the bars.
+  #[serde(rename = "timeframe")]
+  pub timeframe: TimeFrame,
+  /// The adjustment to use (defaults to raw)
+  #[serde(rename = "adjustment")]
+  pub adjustment: Option<Adjustment>,
+  /// The data feed to use.
+  ///
+  /// Defaults to [`IEX`][Feed::IEX] for free users and
+  /// [`SIP`][Feed::SIP] for users with an unlimited subscription.
+  #[serde(rename = "feed")]
+  pub feed: Option<Feed>,
+  /// If provided we will pass a page token to continue where we left off.
+  #[serde(rename = "page_token", skip_serializing_if = "Option::is_none")]
+  pub page_token: Option<String>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  #[serde(skip)]
+  pub _non_exhaustive: (),
+}
+
+
+/// A helper for initializing [`ListMultiReq`] objects.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ListMultiReqInit {
+  /// See `ListMultiReq::limit`.
+  pub limit: Option<usize>,
+  /// See `ListMultiReq::adjustment`.
+  pub adjustment: Option<Adjustment>,
+  /// See `ListMultiReq::feed`.
+  pub feed: Option<Feed>,
+  /// See `ListMultiReq::page_token`.
+  pub page_token: Option<String>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  pub _non_exhaustive: (),
+}
+
+impl ListMultiReqInit {
+  /// Create a [`ListMultiReq`] from a `ListMultiReqInit`.
+  #[inline]
+  pub fn init<I, S>(
+    self,
+    symbols: I,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    timeframe: TimeFrame,
+  ) -> ListMultiReq
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    ListMultiReq {
+      symbols: symbols.into_iter().map(S::into).collect(),
+      start,
+      end,
+      timeframe,
+      adjustment: self.adjustment,
+      limit: self.limit,
+      feed: self.feed,
+      page_token: self.page_token,
+      _non_exhaustive: (),
+    }
+  }
+}
+
+
+/// A collection of bars for multiple symbols as returned by the API.
+/// This is one page of bars.
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub struct MultiBars {
+  /// The returned bars, keyed by symbol.
+  #[serde(rename = "bars", default, deserialize_with = "map_from_str")]
+  pub bars: BTreeMap<String, Vec<Bar>>,
+  /// The token to provide to a request to get the next page of bars
+  /// for this request.
+  #[serde(rename = "next_page_token")]
+  pub next_page_token: Option<String>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  #[serde(skip)]
+  pub _non_exhaustive: (),
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the /v2/stocks/bars endpoint.
+  pub ListMulti(ListMultiReq),
+  Ok => MultiBars, [
+    /// The market data was retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => ListMultiError, [
+    /// A query parameter was invalid.
+    /* 400 */ BAD_REQUEST => InvalidInput,
+  ]
+
+  fn base_url() -> Option<Str> {
+    data::base_url()
+  }
+
+  fn path(_input: &Self::Input) -> Str {
+    "/v2/stocks/bars".into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    Ok(Some(to_query(input)?.into()))
+  }
+}
+
+/// Pagination spans the entire set of symbols requested. Items are
+/// reported along with the symbol they belong to.
+impl Paginated for ListMulti {
+  type Item = (String, Bar);
+
+  #[inline]
+  fn set_page_token(input: &mut Self::Input, token: String) {
+    input.page_token = Some(token);
+  }
+
+  #[inline]
+  fn into_items(output: Self::Output) -> (Vec<Self::Item>, Option<String>) {
+    let items = output
+      .bars
+      .into_iter()
+      .flat_map(|(symbol, items)| items.into_iter().map(move |item| (symbol.clone(), item)))
+      .collect();
+    (items, output.next_page_token)
+  }
+}
+
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -291,6 +463,55 @@ mod tests {
     assert!(res.next_page_token.is_some())
   }
 
+  /// Verify that we can properly parse a reference multi-symbol bar
+  /// response and flatten it for pagination.
+  #[test]
+  fn parse_reference_multi_bars() {
+    let response = r#"{
+    "bars": {
+      "AAPL": [
+        {"t": "2021-02-01T16:01:00Z", "o": 133.32, "h": 133.74, "l": 133.31, "c": 133.5, "v": 9876, "n": 1, "vw": 133.5}
+      ],
+      "MSFT": [
+        {"t": "2021-02-01T16:01:00Z", "o": 232.1, "h": 232.4, "l": 232.0, "c": 232.2, "v": 1234, "n": 1, "vw": 232.2},
+        {"t": "2021-02-01T16:02:00Z", "o": 232.2, "h": 232.5, "l": 232.1, "c": 232.3, "v": 4321, "n": 1, "vw": 232.3}
+      ]
+    },
+    "next_page_token": "QUFQTHxNfDIwMjEtMDItMDFUMTY6MDI6MDAuMDAwMDAwMDAwWg=="
+}"#;
+
+    let res = from_json::<<ListMulti as Endpoint>::Output>(response).unwrap();
+    assert_eq!(res.bars.len(), 2);
+    assert_eq!(res.bars["AAPL"].len(), 1);
+    assert_eq!(res.bars["MSFT"].len(), 2);
+    assert_eq!(res.bars["MSFT"][1].volume, 4321);
+    assert!(res.next_page_token.is_some());
+
+    let (items, token) = ListMulti::into_items(res);
+    let symbols = items
+      .iter()
+      .map(|(symbol, _bar)| symbol.as_str())
+      .collect::<Vec<_>>();
+    assert_eq!(symbols, ["AAPL", "MSFT", "MSFT"]);
+    assert!(token.is_some());
+
+    let response = r#"{"bars": null, "next_page_token": null}"#;
+    let res = from_json::<<ListMulti as Endpoint>::Output>(response).unwrap();
+    assert!(res.bars.is_empty());
+  }
+
+  /// Check that we serialize multi-symbol bar requests correctly.
+  #[test]
+  fn serialize_multi_request() {
+    let start = DateTime::from_str("2021-02-01T16:00:00Z").unwrap();
+    let end = DateTime::from_str("2021-02-01T17:00:00Z").unwrap();
+    let request = ListMultiReqInit::default().init(["AAPL", "MSFT"], start, end, TimeFrame::OneDay);
+
+    assert_eq!(ListMulti::path(&request), "/v2/stocks/bars");
+    let query = ListMulti::query(&request).unwrap().unwrap();
+    assert!(query.starts_with("symbols=AAPL%2CMSFT&"), "{query}");
+  }
+
   /// Check that we can decode a response containing no bars correctly.
   #[test(tokio::test)]
   async fn no_bars() {
@@ -342,6 +563,22 @@ mod tests {
     assert_in(&bars[1].weighted_average, 167..=173);
   }
 
+  /// Check that we can request historic bar data for multiple stocks.
+  #[test(tokio::test)]
+  async fn request_multi_bars() {
+    let api_info = ApiInfo::from_env().unwrap();
+    let client = Client::new(api_info);
+    let start = DateTime::from_str("2018-12-03T21:47:00Z").unwrap();
+    let end = DateTime::from_str("2018-12-06T21:47:00Z").unwrap();
+    let request = ListMultiReqInit::default().init(["AAPL", "MSFT"], start, end, TimeFrame::OneDay);
+
+    let res = client.issue::<ListMulti>(&request).await.unwrap();
+    assert_eq!(res.bars.len(), 2);
+    assert_eq!(res.bars["AAPL"].len(), 2);
+    assert_eq!(res.bars["MSFT"].len(), 2);
+    assert_in(&res.bars["AAPL"][0].open, 179..=182);
+  }
+
   /// Verify that we can request data through a provided page token.
   #[test(tokio::test)]
   async fn can_follow_pagination() {
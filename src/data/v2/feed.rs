@@ -1,11 +1,12 @@
 // Copyright (C) 2022 The apca Developers
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use serde::Deserialize;
 use serde::Serialize;
 
 
 /// An enumeration of the different supported data feeds.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize)]
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[non_exhaustive]
 pub enum Feed {
   /// Use the Investors Exchange (IEX) as the data source.
@@ -21,3 +22,14 @@ pub enum Feed {
   #[serde(rename = "sip")]
   SIP,
 }
+
+impl Feed {
+  /// Retrieve the textual representation of the feed, as used by the
+  /// API.
+  pub(crate) fn as_str(&self) -> &'static str {
+    match self {
+      Self::IEX => "iex",
+      Self::SIP => "sip",
+    }
+  }
+}
@@ -0,0 +1,206 @@
+// Copyright (C) 2024 The apca Developers
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::from_slice as from_json;
+use serde_urlencoded::to_string as to_query;
+
+use crate::data;
+use crate::data::v2::bars::Bar;
+use crate::data::v2::Feed;
+use crate::util::string_slice_to_str;
+use crate::Str;
+
+
+/// A GET request to be made to the /v2/stocks/bars/latest endpoint.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct GetReq {
+  /// The symbols to retrieve the latest bar for.
+  #[serde(rename = "symbols", serialize_with = "string_slice_to_str")]
+  pub symbols: Vec<String>,
+  /// The data feed to use.
+  #[serde(rename = "feed")]
+  pub feed: Option<Feed>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  #[serde(skip)]
+  pub _non_exhaustive: (),
+}
+
+
+/// A helper for initializing [`GetReq`] objects.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[allow(missing_copy_implementations)]
+pub struct GetReqInit {
+  /// See `GetReq::feed`.
+  pub feed: Option<Feed>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  pub _non_exhaustive: (),
+}
+
+impl GetReqInit {
+  /// Create a [`GetReq`] from a `GetReqInit`.
+  #[inline]
+  pub fn init<I, S>(self, symbols: I) -> GetReq
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    GetReq {
+      symbols: symbols.into_iter().map(S::into).collect(),
+      feed: self.feed,
+      _non_exhaustive: (),
+    }
+  }
+}
+
+
+EndpointNoParse! {
+  /// The representation of a GET request to the
+  /// /v2/stocks/bars/latest endpoint.
+  pub Get(GetReq),
+  Ok => Vec<(String, Bar)>, [
+    /// The latest bars were retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => GetError, [
+    /// The provided symbol was invalid or not found or the data feed is
+    /// not supported.
+    /* 400 */ BAD_REQUEST => InvalidInput,
+  ]
+
+  fn base_url() -> Option<Str> {
+    data::base_url()
+  }
+
+  fn path(_input: &Self::Input) -> Str {
+    "/v2/stocks/bars/latest".into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    Ok(Some(to_query(input)?.into()))
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    /// A helper object for parsing the response to a `Get` request.
+    #[derive(Deserialize)]
+    struct Response {
+      /// A mapping from symbols to bar objects.
+      // We use a `BTreeMap` here to have a consistent ordering of
+      // bars.
+      bars: BTreeMap<String, Bar>,
+    }
+
+    from_json::<Response>(body)
+      .map(|response| response.bars.into_iter().collect())
+      .map_err(Self::ConversionError::from)
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    from_json::<Self::ApiError>(body).map_err(|_| body.to_vec())
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use chrono::DateTime;
+  use chrono::Duration;
+  use chrono::Utc;
+
+  use http_endpoint::Endpoint as _;
+
+  use num_decimal::Num;
+
+  use test_log::test;
+
+  use crate::api_info::ApiInfo;
+  use crate::Client;
+  use crate::RequestError;
+
+
+  /// Check that we can parse the reference latest bars from the
+  /// documentation.
+  #[test]
+  fn parse_reference_bars() {
+    let response = br#"{
+      "bars": {
+        "TSLA": {
+          "t": "2022-04-12T17:04:00Z",
+          "o": 996.46,
+          "h": 997.39,
+          "l": 996.46,
+          "c": 997.39,
+          "v": 1231,
+          "n": 38,
+          "vw": 996.937
+        },
+        "AAPL": {
+          "t": "2022-04-12T17:04:00Z",
+          "o": 167.88,
+          "h": 167.9,
+          "l": 167.79,
+          "c": 167.8,
+          "v": 3386,
+          "n": 41,
+          "vw": 167.83
+        }
+      }
+    }"#;
+
+    let bars = Get::parse(response).unwrap();
+    assert_eq!(bars.len(), 2);
+
+    assert_eq!(bars[0].0, "AAPL");
+    let aapl = &bars[0].1;
+    assert_eq!(
+      aapl.time,
+      DateTime::parse_from_rfc3339("2022-04-12T17:04:00Z").unwrap()
+    );
+    assert_eq!(aapl.open, Num::new(16788, 100));
+    assert_eq!(aapl.close, Num::new(1678, 10));
+    assert_eq!(aapl.volume, 3386);
+
+    assert_eq!(bars[1].0, "TSLA");
+    assert_eq!(bars[1].1.weighted_average, Num::new(996937, 1000));
+  }
+
+
+  /// Verify that we can retrieve the latest bar for multiple
+  /// assets.
+  #[test(tokio::test)]
+  async fn request_latest_bars() {
+    let api_info = ApiInfo::from_env().unwrap();
+    let client = Client::new(api_info);
+
+    let req = GetReqInit::default().init(["SPY", "AAPL"]);
+    let bars = client.issue::<Get>(&req).await.unwrap();
+    assert_eq!(bars.len(), 2);
+    assert_eq!(bars[0].0, "AAPL");
+    assert_eq!(bars[1].0, "SPY");
+    // Just as a rough sanity check, we require that the reported time
+    // is some time after two weeks before today.
+    assert!(bars[1].1.time >= Utc::now() - Duration::try_weeks(2).unwrap());
+  }
+
+  /// Verify that we error out as expected when attempting to retrieve
+  /// the latest bar for an invalid symbol.
+  #[test(tokio::test)]
+  async fn invalid_symbol() {
+    let api_info = ApiInfo::from_env().unwrap();
+    let client = Client::new(api_info);
+
+    let req = GetReqInit::default().init(["ABC123"]);
+    let err = client.issue::<Get>(&req).await.unwrap_err();
+    match err {
+      RequestError::Endpoint(GetError::InvalidInput(_)) => (),
+      _ => panic!("Received unexpected error: {err:?}"),
+    };
+  }
+}
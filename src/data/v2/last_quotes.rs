@@ -13,8 +13,8 @@ use serde::Serialize;
 use serde_json::from_slice as from_json;
 use serde_urlencoded::to_string as to_query;
 
+use crate::data;
 use crate::data::v2::Feed;
-use crate::data::DATA_BASE_URL;
 use crate::util::string_slice_to_str;
 use crate::Str;
 
@@ -104,7 +104,7 @@ EndpointNoParse! {
   ]
 
   fn base_url() -> Option<Str> {
-    Some(DATA_BASE_URL.into())
+    data::base_url()
   }
 
   fn path(_input: &Self::Input) -> Str {
@@ -0,0 +1,203 @@
+// Copyright (C) 2024 The apca Developers
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::from_slice as from_json;
+use serde_urlencoded::to_string as to_query;
+
+use crate::data;
+use crate::data::v2::trades::Trade;
+use crate::data::v2::Feed;
+use crate::util::string_slice_to_str;
+use crate::Str;
+
+
+/// A GET request to be made to the /v2/stocks/trades/latest endpoint.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct GetReq {
+  /// The symbols to retrieve the latest trade for.
+  #[serde(rename = "symbols", serialize_with = "string_slice_to_str")]
+  pub symbols: Vec<String>,
+  /// The data feed to use.
+  #[serde(rename = "feed")]
+  pub feed: Option<Feed>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  #[serde(skip)]
+  pub _non_exhaustive: (),
+}
+
+
+/// A helper for initializing [`GetReq`] objects.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[allow(missing_copy_implementations)]
+pub struct GetReqInit {
+  /// See `GetReq::feed`.
+  pub feed: Option<Feed>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  pub _non_exhaustive: (),
+}
+
+impl GetReqInit {
+  /// Create a [`GetReq`] from a `GetReqInit`.
+  #[inline]
+  pub fn init<I, S>(self, symbols: I) -> GetReq
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    GetReq {
+      symbols: symbols.into_iter().map(S::into).collect(),
+      feed: self.feed,
+      _non_exhaustive: (),
+    }
+  }
+}
+
+
+EndpointNoParse! {
+  /// The representation of a GET request to the
+  /// /v2/stocks/trades/latest endpoint.
+  pub Get(GetReq),
+  Ok => Vec<(String, Trade)>, [
+    /// The latest trades were retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => GetError, [
+    /// The provided symbol was invalid or not found or the data feed is
+    /// not supported.
+    /* 400 */ BAD_REQUEST => InvalidInput,
+  ]
+
+  fn base_url() -> Option<Str> {
+    data::base_url()
+  }
+
+  fn path(_input: &Self::Input) -> Str {
+    "/v2/stocks/trades/latest".into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    Ok(Some(to_query(input)?.into()))
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    /// A helper object for parsing the response to a `Get` request.
+    #[derive(Deserialize)]
+    struct Response {
+      /// A mapping from symbols to trade objects.
+      // We use a `BTreeMap` here to have a consistent ordering of
+      // trades.
+      trades: BTreeMap<String, Trade>,
+    }
+
+    from_json::<Response>(body)
+      .map(|response| response.trades.into_iter().collect())
+      .map_err(Self::ConversionError::from)
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    from_json::<Self::ApiError>(body).map_err(|_| body.to_vec())
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use chrono::DateTime;
+  use chrono::Duration;
+  use chrono::Utc;
+
+  use http_endpoint::Endpoint as _;
+
+  use num_decimal::Num;
+
+  use test_log::test;
+
+  use crate::api_info::ApiInfo;
+  use crate::Client;
+  use crate::RequestError;
+
+
+  /// Check that we can parse the reference latest trades from the
+  /// documentation.
+  #[test]
+  fn parse_reference_trades() {
+    let response = br#"{
+      "trades": {
+        "TSLA": {
+          "t": "2022-04-12T17:05:06.936423531Z",
+          "x": "V",
+          "p": 995,
+          "s": 100,
+          "c": ["@"],
+          "i": 10741,
+          "z": "C"
+        },
+        "AAPL": {
+          "t": "2022-04-12T17:05:17.428334819Z",
+          "x": "V",
+          "p": 167.86,
+          "s": 99,
+          "c": ["@", "I"],
+          "i": 10327,
+          "z": "C"
+        }
+      }
+    }"#;
+
+    let trades = Get::parse(response).unwrap();
+    assert_eq!(trades.len(), 2);
+
+    assert_eq!(trades[0].0, "AAPL");
+    let aapl = &trades[0].1;
+    assert_eq!(
+      aapl.timestamp,
+      DateTime::parse_from_rfc3339("2022-04-12T17:05:17.428334819Z").unwrap()
+    );
+    assert_eq!(aapl.price, Num::new(16786, 100));
+    assert_eq!(aapl.size, 99);
+
+    assert_eq!(trades[1].0, "TSLA");
+    assert_eq!(trades[1].1.price, Num::new(995, 1));
+  }
+
+
+  /// Verify that we can retrieve the latest trade for multiple
+  /// assets.
+  #[test(tokio::test)]
+  async fn request_latest_trades() {
+    let api_info = ApiInfo::from_env().unwrap();
+    let client = Client::new(api_info);
+
+    let req = GetReqInit::default().init(["SPY", "AAPL"]);
+    let trades = client.issue::<Get>(&req).await.unwrap();
+    assert_eq!(trades.len(), 2);
+    assert_eq!(trades[0].0, "AAPL");
+    assert_eq!(trades[1].0, "SPY");
+    // Just as a rough sanity check, we require that the reported time
+    // is some time after two weeks before today.
+    assert!(trades[1].1.timestamp >= Utc::now() - Duration::try_weeks(2).unwrap());
+  }
+
+  /// Verify that we error out as expected when attempting to retrieve
+  /// the latest trade for an invalid symbol.
+  #[test(tokio::test)]
+  async fn invalid_symbol() {
+    let api_info = ApiInfo::from_env().unwrap();
+    let client = Client::new(api_info);
+
+    let req = GetReqInit::default().init(["ABC123"]);
+    let err = client.issue::<Get>(&req).await.unwrap_err();
+    match err {
+      RequestError::Endpoint(GetError::InvalidInput(_)) => (),
+      _ => panic!("Received unexpected error: {err:?}"),
+    };
+  }
+}
@@ -6,10 +6,16 @@ mod unfold;
 
 /// Definitions for retrieval of market data bars.
 pub mod bars;
+/// Functionality for retrieval of most recent bars.
+pub mod last_bars;
 /// Functionality for retrieval of most recent quotes.
 pub mod last_quotes;
+/// Functionality for retrieval of most recent trades.
+pub mod last_trades;
 /// Functionality for retrieving historic quotes.
 pub mod quotes;
+/// Functionality for retrieval of market snapshots.
+pub mod snapshots;
 /// Definitions for real-time streaming of market data.
 pub mod stream;
 /// Definitions for retrieval of market data trades.
@@ -1,6 +1,8 @@
 // Copyright (C) 2022-2024 The apca Developers
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use std::collections::BTreeMap;
+
 use chrono::DateTime;
 use chrono::Utc;
 
@@ -8,8 +10,11 @@ use serde::Deserialize;
 use serde::Serialize;
 use serde_urlencoded::to_string as to_query;
 
+use crate::data;
 use crate::data::v2::Feed;
-use crate::data::DATA_BASE_URL;
+use crate::paginate::Paginated;
+use crate::util::map_from_str;
+use crate::util::string_slice_to_str;
 use crate::util::vec_from_str;
 use crate::Str;
 
@@ -118,7 +123,7 @@ Endpoint! {
   ]
 
   fn base_url() -> Option<Str> {
-    Some(DATA_BASE_URL.into())
+    data::base_url()
   }
 
   #[inline]
@@ -131,6 +136,156 @@ Endpoint! {
   }
 }
 
+impl Paginated for List {
+  type Item = Quote;
+
+  #[inline]
+  fn set_page_token(input: &mut Self::Input, token: String) {
+    input.page_token = Some(token);
+  }
+
+  #[inline]
+  fn into_items(output: Self::Output) -> (Vec<Self::Item>, Option<String>) {
+    (output.quotes, output.next_page_token)
+  }
+}
+
+
+/// A GET request to be issued to the /v2/stocks/quotes endpoint,
+/// retrieving quotes for multiple symbols at once.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ListMultiReq {
+  /// The symbols for which to retrieve market data.
+  #[serde(rename = "symbols", serialize_with = "string_slice_to_str")]
+  pub symbols: Vec<String>,
+  /// The maximum number of quotes to be returned in total, across all
+  /// symbols.
+  ///
+  /// It can be between 1 and 10000. Defaults to 1000 if the provided
+  /// value is `None`.
+  #[serde(rename = "limit")]
+  pub limit: Option<usize>,
+  /// Filter quotes equal to or after this time.
+  #[serde(rename = "start")]
+  pub start: DateTime<Utc>,
+  /// Filter quotes equal to or before this time.
+  #[serde(rename = "end")]
+  pub end: DateTime<Utc>,
+  /// The data feed to use.
+  ///
+  /// Defaults to [`IEX`][Feed::IEX] for free users and
+  /// [`SIP`][Feed::SIP] for users with an unlimited subscription.
+  #[serde(rename = "feed")]
+  pub feed: Option<Feed>,
+  /// If provided we will pass a page token to continue where we left off.
+  #[serde(rename = "page_token", skip_serializing_if = "Option::is_none")]
+  pub page_token: Option<String>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  #[serde(skip)]
+  pub _non_exhaustive: (),
+}
+
+
+/// A helper for initializing [`ListMultiReq`] objects.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ListMultiReqInit {
+  /// See `ListMultiReq::limit`.
+  pub limit: Option<usize>,
+  /// See `ListMultiReq::feed`.
+  pub feed: Option<Feed>,
+  /// See `ListMultiReq::page_token`.
+  pub page_token: Option<String>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  pub _non_exhaustive: (),
+}
+
+impl ListMultiReqInit {
+  /// Create a [`ListMultiReq`] from a `ListMultiReqInit`.
+  #[inline]
+  pub fn init<I, S>(self, symbols: I, start: DateTime<Utc>, end: DateTime<Utc>) -> ListMultiReq
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    ListMultiReq {
+      symbols: symbols.into_iter().map(S::into).collect(),
+      start,
+      end,
+      limit: self.limit,
+      feed: self.feed,
+      page_token: self.page_token,
+      _non_exhaustive: (),
+    }
+  }
+}
+
+
+/// A collection of quotes for multiple symbols as returned by the API.
+/// This is one page of quotes.
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub struct MultiQuotes {
+  /// The returned quotes, keyed by symbol.
+  #[serde(rename = "quotes", default, deserialize_with = "map_from_str")]
+  pub quotes: BTreeMap<String, Vec<Quote>>,
+  /// The token to provide to a request to get the next page of quotes
+  /// for this request.
+  #[serde(rename = "next_page_token")]
+  pub next_page_token: Option<String>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  #[serde(skip)]
+  pub _non_exhaustive: (),
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the /v2/stocks/quotes endpoint.
+  pub ListMulti(ListMultiReq),
+  Ok => MultiQuotes, [
+    /// The market data was retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => ListMultiError, [
+    /// A query parameter was invalid.
+    /* 400 */ BAD_REQUEST => InvalidInput,
+  ]
+
+  fn base_url() -> Option<Str> {
+    data::base_url()
+  }
+
+  fn path(_input: &Self::Input) -> Str {
+    "/v2/stocks/quotes".into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    Ok(Some(to_query(input)?.into()))
+  }
+}
+
+/// Pagination spans the entire set of symbols requested. Items are
+/// reported along with the symbol they belong to.
+impl Paginated for ListMulti {
+  type Item = (String, Quote);
+
+  #[inline]
+  fn set_page_token(input: &mut Self::Input, token: String) {
+    input.page_token = Some(token);
+  }
+
+  #[inline]
+  fn into_items(output: Self::Output) -> (Vec<Self::Item>, Option<String>) {
+    let items = output
+      .quotes
+      .into_iter()
+      .flat_map(|(symbol, items)| items.into_iter().map(move |item| (symbol.clone(), item)))
+      .collect();
+    (items, output.next_page_token)
+  }
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -138,8 +293,12 @@ mod tests {
 
   use std::str::FromStr as _;
 
+  use http_endpoint::Endpoint;
+
   use num_decimal::Num;
 
+  use serde_json::from_str as from_json;
+
   use test_log::test;
 
   use crate::api_info::ApiInfo;
@@ -147,6 +306,29 @@ mod tests {
   use crate::RequestError;
 
 
+  /// Verify that we can properly parse a reference multi-symbol quotes
+  /// response.
+  #[test]
+  fn parse_reference_multi_quotes() {
+    let response = r#"{
+    "quotes": {
+      "AAPL": [
+        {"t": "2022-01-04T13:35:59.916Z", "ax": "Q", "ap": 179.1, "as": 1, "bx": "Q", "bp": 179.0, "bs": 4, "c": ["R"], "z": "C"}
+      ],
+      "MSFT": [
+        {"t": "2022-01-04T13:35:59.916Z", "ax": "Q", "ap": 334.0, "as": 2, "bx": "Q", "bp": 333.9, "bs": 3, "c": ["R"], "z": "C"}
+      ]
+    },
+    "next_page_token": "TVNGVHwyMDIyLTAxLTA0VDEzOjM1OjU5LjkxNlo="
+}"#;
+
+    let res = from_json::<<ListMulti as Endpoint>::Output>(response).unwrap();
+    assert_eq!(res.quotes.len(), 2);
+    assert_eq!(res.quotes["AAPL"][0].ask_price, Num::new(1791, 10));
+    assert_eq!(res.quotes["MSFT"][0].bid_size, 3);
+    assert!(res.next_page_token.is_some());
+  }
+
   /// Check that we can retrieve quotes for a specific time frame.
   #[test(tokio::test)]
   async fn request_quotes() {
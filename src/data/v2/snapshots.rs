@@ -0,0 +1,290 @@
+// Copyright (C) 2024 The apca Developers
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::from_slice as from_json;
+use serde_urlencoded::to_string as to_query;
+
+use crate::data;
+use crate::data::v2::bars::Bar;
+use crate::data::v2::last_quotes::Quote;
+use crate::data::v2::trades::Trade;
+use crate::data::v2::Feed;
+use crate::util::string_slice_to_str;
+use crate::Str;
+
+
+/// A GET request to be made to the /v2/stocks/snapshots endpoint.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct GetReq {
+  /// The symbols to retrieve snapshots for.
+  #[serde(rename = "symbols", serialize_with = "string_slice_to_str")]
+  pub symbols: Vec<String>,
+  /// The data feed to use.
+  #[serde(rename = "feed")]
+  pub feed: Option<Feed>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  #[serde(skip)]
+  pub _non_exhaustive: (),
+}
+
+
+/// A helper for initializing [`GetReq`] objects.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[allow(missing_copy_implementations)]
+pub struct GetReqInit {
+  /// See `GetReq::feed`.
+  pub feed: Option<Feed>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  pub _non_exhaustive: (),
+}
+
+impl GetReqInit {
+  /// Create a [`GetReq`] from a `GetReqInit`.
+  #[inline]
+  pub fn init<I, S>(self, symbols: I) -> GetReq
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    GetReq {
+      symbols: symbols.into_iter().map(S::into).collect(),
+      feed: self.feed,
+      _non_exhaustive: (),
+    }
+  }
+}
+
+
+/// A snapshot of the current market state of a symbol, as returned by
+/// the /v2/stocks/snapshots endpoint.
+///
+/// Any of the members may be absent, e.g., for symbols that have not
+/// been traded recently.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+pub struct Snapshot {
+  /// The latest trade.
+  #[serde(rename = "latestTrade")]
+  pub latest_trade: Option<Trade>,
+  /// The latest quote.
+  #[serde(rename = "latestQuote")]
+  pub latest_quote: Option<Quote>,
+  /// The current minute bar.
+  #[serde(rename = "minuteBar")]
+  pub minute_bar: Option<Bar>,
+  /// The current daily bar.
+  #[serde(rename = "dailyBar")]
+  pub daily_bar: Option<Bar>,
+  /// The previous daily bar.
+  #[serde(rename = "prevDailyBar")]
+  pub prev_daily_bar: Option<Bar>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  #[serde(skip)]
+  pub _non_exhaustive: (),
+}
+
+
+EndpointNoParse! {
+  /// The representation of a GET request to the /v2/stocks/snapshots
+  /// endpoint.
+  pub Get(GetReq),
+  Ok => Vec<(String, Snapshot)>, [
+    /// The snapshots were retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => GetError, [
+    /// The provided symbol was invalid or not found or the data feed is
+    /// not supported.
+    /* 400 */ BAD_REQUEST => InvalidInput,
+  ]
+
+  fn base_url() -> Option<Str> {
+    data::base_url()
+  }
+
+  fn path(_input: &Self::Input) -> Str {
+    "/v2/stocks/snapshots".into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    Ok(Some(to_query(input)?.into()))
+  }
+
+  fn parse(body: &[u8]) -> Result<Self::Output, Self::ConversionError> {
+    // The response is a mapping from symbols to snapshot objects. We
+    // use a `BTreeMap` here to have a consistent ordering of snapshots.
+    // Symbols without any data are reported as `null`, and we skip
+    // those.
+    from_json::<BTreeMap<String, Option<Snapshot>>>(body)
+      .map(|snapshots| {
+        snapshots
+          .into_iter()
+          .filter_map(|(symbol, snapshot)| snapshot.map(|snapshot| (symbol, snapshot)))
+          .collect()
+      })
+      .map_err(Self::ConversionError::from)
+  }
+
+  fn parse_err(body: &[u8]) -> Result<Self::ApiError, Vec<u8>> {
+    from_json::<Self::ApiError>(body).map_err(|_| body.to_vec())
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use chrono::DateTime;
+  use chrono::Duration;
+  use chrono::Utc;
+
+  use http_endpoint::Endpoint as _;
+
+  use num_decimal::Num;
+
+  use test_log::test;
+
+  use crate::api_info::ApiInfo;
+  use crate::Client;
+  use crate::RequestError;
+
+
+  /// Check that we can parse the reference snapshots from the
+  /// documentation.
+  #[test]
+  fn parse_reference_snapshots() {
+    let response = br#"{
+      "AAPL": {
+        "latestTrade": {
+          "t": "2021-05-11T20:00:00.435997104Z",
+          "x": "Q",
+          "p": 125.91,
+          "s": 5589631,
+          "c": ["@", "M"],
+          "i": 179430,
+          "z": "C"
+        },
+        "latestQuote": {
+          "t": "2021-05-11T21:59:57.617565039Z",
+          "ax": "P",
+          "ap": 125.68,
+          "as": 12,
+          "bx": "P",
+          "bp": 125.6,
+          "bs": 4,
+          "c": ["R"],
+          "z": "C"
+        },
+        "minuteBar": {
+          "t": "2021-05-11T22:02:00Z",
+          "o": 125.66,
+          "h": 125.66,
+          "l": 125.66,
+          "c": 125.66,
+          "v": 396,
+          "n": 3,
+          "vw": 125.66
+        },
+        "dailyBar": {
+          "t": "2021-05-11T04:00:00Z",
+          "o": 123.5,
+          "h": 126.27,
+          "l": 122.77,
+          "c": 125.91,
+          "v": 125863164,
+          "n": 880542,
+          "vw": 124.94
+        },
+        "prevDailyBar": {
+          "t": "2021-05-10T04:00:00Z",
+          "o": 129.41,
+          "h": 129.54,
+          "l": 126.81,
+          "c": 126.85,
+          "v": 88071229,
+          "n": 590985,
+          "vw": 127.91
+        }
+      },
+      "NOSUCHSYMBOL": null,
+      "ABC": {
+        "latestTrade": {
+          "t": "2021-05-11T20:00:00.435997104Z",
+          "x": "Q",
+          "p": 1,
+          "s": 1,
+          "c": ["@"],
+          "i": 1,
+          "z": "C"
+        }
+      }
+    }"#;
+
+    let snapshots = Get::parse(response).unwrap();
+    assert_eq!(snapshots.len(), 2);
+
+    assert_eq!(snapshots[0].0, "AAPL");
+    let aapl = &snapshots[0].1;
+    let trade = aapl.latest_trade.as_ref().unwrap();
+    assert_eq!(
+      trade.timestamp,
+      DateTime::parse_from_rfc3339("2021-05-11T20:00:00.435997104Z").unwrap()
+    );
+    assert_eq!(trade.price, Num::new(12591, 100));
+    assert_eq!(
+      aapl.latest_quote.as_ref().unwrap().ask_price,
+      Num::new(12568, 100)
+    );
+    assert_eq!(aapl.minute_bar.as_ref().unwrap().volume, 396);
+    assert_eq!(aapl.daily_bar.as_ref().unwrap().close, Num::new(12591, 100));
+    assert_eq!(
+      aapl.prev_daily_bar.as_ref().unwrap().open,
+      Num::new(12941, 100)
+    );
+
+    assert_eq!(snapshots[1].0, "ABC");
+    let abc = &snapshots[1].1;
+    assert!(abc.latest_trade.is_some());
+    assert_eq!(abc.latest_quote, None);
+    assert_eq!(abc.daily_bar, None);
+  }
+
+  /// Verify that we can retrieve snapshots for multiple assets.
+  #[test(tokio::test)]
+  async fn request_snapshots() {
+    let api_info = ApiInfo::from_env().unwrap();
+    let client = Client::new(api_info);
+
+    let req = GetReqInit::default().init(["SPY", "AAPL"]);
+    let snapshots = client.issue::<Get>(&req).await.unwrap();
+    assert_eq!(snapshots.len(), 2);
+    assert_eq!(snapshots[0].0, "AAPL");
+    assert_eq!(snapshots[1].0, "SPY");
+
+    let trade = snapshots[1].1.latest_trade.as_ref().unwrap();
+    assert!(trade.timestamp >= Utc::now() - Duration::try_weeks(2).unwrap());
+    assert!(snapshots[1].1.prev_daily_bar.is_some());
+  }
+
+  /// Verify that we error out as expected when attempting to retrieve
+  /// a snapshot for an invalid symbol.
+  #[test(tokio::test)]
+  async fn invalid_symbol() {
+    let api_info = ApiInfo::from_env().unwrap();
+    let client = Client::new(api_info);
+
+    let req = GetReqInit::default().init(["ABC123"]);
+    let err = client.issue::<Get>(&req).await.unwrap_err();
+    match err {
+      RequestError::Endpoint(GetError::InvalidInput(_)) => (),
+      _ => panic!("Received unexpected error: {err:?}"),
+    };
+  }
+}
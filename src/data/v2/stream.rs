@@ -43,6 +43,7 @@ use tokio::net::TcpStream;
 use tungstenite::MaybeTlsStream;
 use tungstenite::WebSocketStream;
 
+use url::ParseError;
 use url::Url;
 
 use websocket_util::subscribe;
@@ -52,12 +53,17 @@ use websocket_util::wrap;
 use websocket_util::wrap::Wrapper;
 
 use super::unfold::Unfold;
+use super::Feed;
 
 use crate::subscribable::Subscribable;
+use crate::telemetry;
 use crate::websocket::connect;
 use crate::websocket::MessageResult;
+use crate::websocket::StreamConfig;
 use crate::ApiInfo;
+use crate::Credentials;
 use crate::Error;
+use crate::Secret;
 use crate::Str;
 
 
@@ -91,6 +97,11 @@ pub enum SourceVariant {
   /// The source provided is a path component to be appended to an
   /// already present base URL.
   PathComponent(&'static str),
+  /// The source provided is a complete path to be used with an already
+  /// present base URL.
+  Path(&'static str),
+  /// The source is the feed configured in the [`ApiInfo`] object.
+  Configured,
   /// The source provided is a complete URL.
   Url(String),
 }
@@ -141,6 +152,39 @@ impl Source for SIP {
 impl private::Sealed for SIP {}
 
 
+/// Use the data feed configured via [`ApiInfo::feed`] as the data
+/// source, falling back to [`IEX`] if none is set.
+#[derive(Clone, Copy, Debug)]
+pub enum ConfiguredFeed {}
+
+impl Source for ConfiguredFeed {
+  #[inline]
+  fn source() -> SourceVariant {
+    SourceVariant::Configured
+  }
+}
+
+impl private::Sealed for ConfiguredFeed {}
+
+
+/// Use Alpaca's crypto currency market data as the data source.
+///
+/// Symbols are currency pairs such as `BTC/USD`. In addition to bars,
+/// quotes, and trades, this source supports subscribing to
+/// [orderbooks][MarketData::orderbooks].
+#[derive(Clone, Copy, Debug)]
+pub enum Crypto {}
+
+impl Source for Crypto {
+  #[inline]
+  fn source() -> SourceVariant {
+    SourceVariant::Path("v1beta3/crypto/us")
+  }
+}
+
+impl private::Sealed for Crypto {}
+
+
 /// A realtime data source that uses a custom URL.
 ///
 /// This type provides a way to stream realtime data from a custom URL.
@@ -152,14 +196,14 @@ impl private::Sealed for SIP {}
 /// # use apca::Client;
 /// # use apca::data::v2::stream::CustomUrl;
 /// # use apca::data::v2::stream::RealtimeData;
-/// // The v1beta3 crypto API happens to be using the same handshake as
-/// // the v2 stock APIs.
+/// // Alpaca's test stream provides data for a fake symbol outside of
+/// // regular market hours.
 /// #[derive(Default)]
-/// struct Crypto;
+/// struct Test;
 ///
-/// impl ToString for Crypto {
+/// impl ToString for Test {
 ///   fn to_string(&self) -> String {
-///     "wss://stream.data.alpaca.markets/v1beta3/crypto/us".into()
+///     "wss://stream.data.alpaca.markets/v2/test".into()
 ///   }
 /// }
 ///
@@ -167,7 +211,7 @@ impl private::Sealed for SIP {}
 /// let client = Client::new(api_info);
 /// # tokio::runtime::Runtime::new().unwrap().block_on(async move {
 /// let (mut stream, mut subscription) = client
-///   .subscribe::<RealtimeData<CustomUrl<Crypto>>>()
+///   .subscribe::<RealtimeData<CustomUrl<Test>>>()
 ///   .await
 ///   .unwrap();
 /// # })
@@ -321,6 +365,42 @@ pub struct Trade {
 }
 
 
+/// A single price level of an order book.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct OrderbookEntry {
+  /// The price level.
+  #[serde(rename = "p")]
+  pub price: Num,
+  /// The aggregate size at this price level.
+  #[serde(rename = "s")]
+  pub size: Num,
+}
+
+
+/// An order book update for a crypto currency pair.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Orderbook {
+  /// The order book's symbol.
+  #[serde(rename = "S")]
+  pub symbol: String,
+  /// The bid side price levels that changed. A size of zero indicates
+  /// that the price level was removed.
+  #[serde(rename = "b", default)]
+  pub bids: Vec<OrderbookEntry>,
+  /// The ask side price levels that changed. A size of zero indicates
+  /// that the price level was removed.
+  #[serde(rename = "a", default)]
+  pub asks: Vec<OrderbookEntry>,
+  /// Whether this update contains the full order book, replacing any
+  /// previously received state, instead of just changed levels.
+  #[serde(rename = "r", default)]
+  pub reset: bool,
+  /// The order book's time stamp.
+  #[serde(rename = "t")]
+  pub timestamp: DateTime<Utc>,
+}
+
+
 /// An error as reported by the Alpaca Stream API.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, ThisError)]
 #[error("{message} ({code})")]
@@ -350,6 +430,9 @@ pub enum DataMessage<B = Bar, Q = Quote, T = Trade> {
   /// A variant representing a trade for a given symbol.
   #[serde(rename = "t")]
   Trade(T),
+  /// A variant representing an order book update for a given symbol.
+  #[serde(rename = "o")]
+  Orderbook(Orderbook),
   /// A control message describing the current list of subscriptions.
   #[serde(rename = "subscription")]
   Subscription(MarketData),
@@ -362,6 +445,21 @@ pub enum DataMessage<B = Bar, Q = Quote, T = Trade> {
   Error(StreamApiError),
 }
 
+impl<B, Q, T> DataMessage<B, Q, T> {
+  /// Retrieve a textual representation of the message's kind.
+  fn kind(&self) -> &'static str {
+    match self {
+      Self::Bar(..) => "bar",
+      Self::Quote(..) => "quote",
+      Self::Trade(..) => "trade",
+      Self::Orderbook(..) => "orderbook",
+      Self::Subscription(..) => "subscription",
+      Self::Success => "success",
+      Self::Error(..) => "error",
+    }
+  }
+}
+
 
 /// A data item as received over our websocket channel.
 #[derive(Debug)]
@@ -373,6 +471,8 @@ pub enum Data<B = Bar, Q = Quote, T = Trade> {
   Quote(Q),
   /// A variant representing trade data for a given symbol.
   Trade(T),
+  /// A variant representing an order book update for a given symbol.
+  Orderbook(Orderbook),
 }
 
 impl Data {
@@ -393,6 +493,12 @@ impl Data {
   pub fn is_trade(&self) -> bool {
     matches!(self, Self::Trade(..))
   }
+
+  /// Check whether this object is of the `Orderbook` variant.
+  #[inline]
+  pub fn is_orderbook(&self) -> bool {
+    matches!(self, Self::Orderbook(..))
+  }
 }
 
 
@@ -428,6 +534,9 @@ impl<B, Q, T> subscribe::Message for ParsedMessage<B, Q, T> {
         DataMessage::Trade(trade) => {
           subscribe::Classification::UserMessage(Ok(Ok(Data::Trade(trade))))
         },
+        DataMessage::Orderbook(orderbook) => {
+          subscribe::Classification::UserMessage(Ok(Ok(Data::Orderbook(orderbook))))
+        },
         DataMessage::Subscription(data) => {
           subscribe::Classification::ControlMessage(ControlMessage::Subscription(data))
         },
@@ -587,6 +696,15 @@ pub struct MarketData {
   /// The trades to subscribe to.
   #[serde(default)]
   pub trades: Symbols,
+  /// The order books to subscribe to.
+  ///
+  /// Order books are only available for the [`Crypto`] source.
+  #[serde(default, skip_serializing_if = "Symbols::is_empty")]
+  pub orderbooks: Symbols,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  #[serde(skip)]
+  pub _non_exhaustive: (),
 }
 
 impl MarketData {
@@ -619,6 +737,16 @@ impl MarketData {
   {
     self.trades = Symbols::List(symbols.into());
   }
+
+  /// A convenience function for setting the
+  /// [`orderbooks`][MarketData::orderbooks] member.
+  #[inline]
+  pub fn set_orderbooks<S>(&mut self, symbols: S)
+  where
+    S: Into<SymbolList>,
+  {
+    self.orderbooks = Symbols::List(symbols.into());
+  }
 }
 
 
@@ -632,9 +760,9 @@ pub enum Request<'d> {
   #[serde(rename = "auth")]
   Authenticate {
     #[serde(rename = "key")]
-    key_id: Cow<'d, str>,
+    key_id: Cow<'d, Secret>,
     #[serde(rename = "secret")]
-    secret: Cow<'d, str>,
+    secret: Cow<'d, Secret>,
   },
   /// A control message subscribing the client to receive updates for
   /// the provided symbols.
@@ -682,12 +810,19 @@ where
   /// Authenticate the connection using Alpaca credentials.
   async fn authenticate(
     &mut self,
-    key_id: &str,
-    secret: &str,
+    credentials: &Credentials,
   ) -> Result<Result<(), Error>, S::Error> {
-    let request = Request::Authenticate {
-      key_id: key_id.into(),
-      secret: secret.into(),
+    let request = match credentials {
+      Credentials::KeyPair { key_id, secret } => Request::Authenticate {
+        key_id: Cow::Borrowed(key_id),
+        secret: Cow::Borrowed(secret),
+      },
+      // OAuth access tokens are conveyed in place of the secret, with
+      // the key being set to a fixed marker value.
+      Credentials::OAuth { token } => Request::Authenticate {
+        key_id: Cow::Owned(Secret::from("oauth")),
+        secret: Cow::Borrowed(token),
+      },
     };
     let json = match to_json(&request) {
       Ok(json) => json,
@@ -796,6 +931,23 @@ type Stream<B, Q, T> = Map<
 >;
 
 
+/// Determine the URL to connect to for streaming real time data from
+/// the provided source.
+fn stream_url(source: SourceVariant, api_info: &ApiInfo) -> Result<Url, ParseError> {
+  let mut url = api_info.data_stream_base_url.clone();
+  match source {
+    SourceVariant::PathComponent(component) => url.set_path(&format!("v2/{component}")),
+    SourceVariant::Configured => {
+      let feed = api_info.feed.unwrap_or(Feed::IEX);
+      url.set_path(&format!("v2/{}", feed.as_str()))
+    },
+    SourceVariant::Path(path) => url.set_path(path),
+    SourceVariant::Url(custom) => url = Url::parse(&custom)?,
+  }
+  Ok(url)
+}
+
+
 /// A type used for requesting a subscription to real time market
 /// data.
 ///
@@ -806,7 +958,13 @@ type Stream<B, Q, T> = Map<
 #[derive(Debug)]
 pub struct RealtimeData<S, B = Bar, Q = Quote, T = Trade> {
   /// Phantom data to make sure that we "use" `S`.
-  _phantom: PhantomData<(S, B, Q, T)>,
+  ///
+  /// We never actually own an `S`, which is reflected by using a
+  /// function pointer here. That keeps the type `Send` and `Sync`
+  /// irrespective of the source.
+  _source: PhantomData<fn() -> S>,
+  /// Phantom data to make sure that we "use" `B`, `Q`, and `T`.
+  _phantom: PhantomData<(B, Q, T)>,
 }
 
 #[async_trait]
@@ -822,6 +980,13 @@ where
   type Stream = Fuse<MessageStream<SplitStream<Stream<B, Q, T>>, ParsedMessage<B, Q, T>>>;
 
   async fn connect(api_info: &Self::Input) -> Result<(Self::Stream, Self::Subscription), Error> {
+    Self::connect_with(api_info, &StreamConfig::default()).await
+  }
+
+  async fn connect_with(
+    api_info: &Self::Input,
+    config: &StreamConfig,
+  ) -> Result<(Self::Stream, Self::Subscription), Error> {
     fn parse<B, Q, T>(
       result: Result<wrap::Message, WebSocketError>,
     ) -> Result<Result<Vec<DataMessage<B, Q, T>>, JsonError>, WebSocketError>
@@ -830,30 +995,27 @@ where
       Q: DeserializeOwned,
       T: DeserializeOwned,
     {
-      result.map(|message| match message {
-        wrap::Message::Text(string) => json_from_str::<Vec<DataMessage<B, Q, T>>>(&string),
-        wrap::Message::Binary(data) => json_from_slice::<Vec<DataMessage<B, Q, T>>>(&data),
+      result.map(|message| {
+        let result = match message {
+          wrap::Message::Text(string) => json_from_str::<Vec<DataMessage<B, Q, T>>>(&string),
+          wrap::Message::Binary(data) => json_from_slice::<Vec<DataMessage<B, Q, T>>>(&data),
+        };
+
+        match &result {
+          Ok(messages) => messages
+            .iter()
+            .for_each(|message| telemetry::record_stream_message("data", message.kind())),
+          Err(..) => telemetry::record_stream_parse_error("data"),
+        }
+        result
       })
     }
 
-    let ApiInfo {
-      data_stream_base_url: url,
-      key_id,
-      secret,
-      ..
-    } = api_info;
-
-    let url = match S::source() {
-      SourceVariant::PathComponent(component) => {
-        let mut url = url.clone();
-        url.set_path(&format!("v2/{}", component));
-        url
-      },
-      SourceVariant::Url(url) => Url::parse(&url)?,
-    };
+    let url = stream_url(S::source(), api_info)?;
+    let credentials = &api_info.credentials;
 
     let stream = Unfold::new(
-      connect(&url)
+      connect(&url, config)
         .await?
         .map(parse::<B, Q, T> as ParseFn<_, _, _>),
     )
@@ -886,7 +1048,7 @@ where
       },
     }
 
-    let authenticate = subscription.authenticate(key_id, secret).boxed();
+    let authenticate = subscription.authenticate(credentials).boxed();
     let () = drive(authenticate, &mut stream).await.map_err(|result| {
       result
         .map(|result| Error::Json(result.unwrap_err()))
@@ -894,6 +1056,7 @@ where
         .unwrap_or_else(|err| err)
     })???;
 
+    let () = telemetry::record_stream_connect("data");
     Ok((stream, subscription))
   }
 }
@@ -909,6 +1072,7 @@ mod tests {
 
   use chrono::DateTime;
 
+  use futures::channel::oneshot;
   use futures::SinkExt as _;
   use futures::TryStreamExt as _;
 
@@ -1226,6 +1390,59 @@ mod tests {
     );
   }
 
+  /// Check that we can deserialize and serialize the
+  /// [`DataMessage::Orderbook`] variant.
+  #[test]
+  fn serialize_deserialize_orderbook() {
+    let json = r#"{
+  "T": "o",
+  "S": "BTC/USD",
+  "t": "2024-03-12T10:38:50.79613221Z",
+  "b": [
+    {"p": 71859.53, "s": 0.27994},
+    {"p": 71849.4, "s": 0}
+  ],
+  "a": [
+    {"p": 71939.7, "s": 0.83953}
+  ],
+  "r": true
+}"#;
+
+    let message = json_from_str::<DataMessage>(json).unwrap();
+    let orderbook = match &message {
+      DataMessage::Orderbook(orderbook) => orderbook,
+      _ => panic!("Decoded unexpected message variant: {message:?}"),
+    };
+    assert_eq!(orderbook.symbol, "BTC/USD");
+    assert_eq!(
+      orderbook.timestamp,
+      DateTime::<Utc>::from_str("2024-03-12T10:38:50.79613221Z").unwrap()
+    );
+    assert_eq!(orderbook.bids.len(), 2);
+    assert_eq!(orderbook.bids[0].price, Num::new(7185953, 100));
+    assert_eq!(orderbook.bids[0].size, Num::new(27994, 100000));
+    assert_eq!(orderbook.bids[1].size, Num::from(0));
+    assert_eq!(orderbook.asks.len(), 1);
+    assert_eq!(orderbook.asks[0].price, Num::new(719397, 10));
+    assert!(orderbook.reset);
+
+    assert_eq!(
+      json_from_str::<DataMessage>(&to_json(&message).unwrap()).unwrap(),
+      message
+    );
+
+    // Incremental updates may omit one side as well as the reset flag.
+    let json = r#"{"T":"o","S":"ETH/USD","t":"2024-03-12T10:38:51Z","a":[{"p":4001,"s":1.5}]}"#;
+    let message = json_from_str::<DataMessage>(json).unwrap();
+    let orderbook = match &message {
+      DataMessage::Orderbook(orderbook) => orderbook,
+      _ => panic!("Decoded unexpected message variant: {message:?}"),
+    };
+    assert!(orderbook.bids.is_empty());
+    assert_eq!(orderbook.asks.len(), 1);
+    assert!(!orderbook.reset);
+  }
+
   /// Check that we can serialize and deserialize the
   /// [`DataMessage::Success`] variant.
   #[test]
@@ -1283,8 +1500,8 @@ mod tests {
   #[test]
   fn serialize_deserialize_authentication_request() {
     let request = Request::Authenticate {
-      key_id: "KEY-ID".into(),
-      secret: "SECRET-KEY".into(),
+      key_id: Cow::Owned(Secret::from("KEY-ID")),
+      secret: Cow::Owned(Secret::from("SECRET-KEY")),
     };
     let json = to_json(&request).unwrap();
     let expected = r#"{"action":"auth","key":"KEY-ID","secret":"SECRET-KEY"}"#;
@@ -1320,6 +1537,48 @@ mod tests {
     assert_eq!(json_from_str::<Request<'_>>(&json).unwrap(), request);
   }
 
+  /// Check that order book subscriptions for crypto currency pairs are
+  /// serialized and deserialized properly.
+  #[test]
+  fn serialize_deserialize_orderbook_subscribe_request() {
+    let mut data = MarketData::default();
+    data.set_trades(["ETH/USD", "BTC/USD"]);
+    data.set_orderbooks(["BTC/USD"]);
+    let request = Request::Subscribe(Cow::Borrowed(&data));
+
+    let json = to_json(&request).unwrap();
+    let expected = r#"{"action":"subscribe","bars":[],"quotes":[],"trades":["BTC/USD","ETH/USD"],"orderbooks":["BTC/USD"]}"#;
+    assert_eq!(json, expected);
+    assert_eq!(json_from_str::<Request<'_>>(&json).unwrap(), request);
+  }
+
+  /// Check that we pick the expected stream URL for the different
+  /// sources.
+  #[test]
+  fn source_stream_url() {
+    let mut api_info = ApiInfo::from_parts(API_BASE_URL, "", "").unwrap();
+    let url = |source, api_info: &ApiInfo| stream_url(source, api_info).unwrap().to_string();
+
+    assert_eq!(
+      url(IEX::source(), &api_info),
+      "wss://stream.data.alpaca.markets/v2/iex"
+    );
+    assert_eq!(
+      url(Crypto::source(), &api_info),
+      "wss://stream.data.alpaca.markets/v1beta3/crypto/us"
+    );
+    assert_eq!(
+      url(ConfiguredFeed::source(), &api_info),
+      "wss://stream.data.alpaca.markets/v2/iex"
+    );
+
+    api_info.feed = Some(Feed::SIP);
+    assert_eq!(
+      url(ConfiguredFeed::source(), &api_info),
+      "wss://stream.data.alpaca.markets/v2/sip"
+    );
+  }
+
   /// Check that we can correctly deserialize a `SymbolList` object.
   #[test]
   fn deserialize_symbol_list() {
@@ -1438,6 +1697,67 @@ mod tests {
     }
   }
 
+  /// Check that we can subscribe to and receive crypto currency order
+  /// book updates.
+  #[test(tokio::test)]
+  async fn subscribe_orderbooks() {
+    // Data received while driving the subscription future is
+    // discarded, so only push the update once subscribing completed.
+    let (subscribed_send, subscribed_recv) = oneshot::channel();
+    let test = |mut stream: WebSocketStream| async move {
+      stream.send(Message::Text(CONN_RESP.to_string())).await?;
+      // Authentication.
+      assert_eq!(
+        stream.next().await.unwrap()?,
+        Message::Text(AUTH_REQ.to_string()),
+      );
+      stream.send(Message::Text(AUTH_RESP.to_string())).await?;
+
+      // Subscription.
+      let sub_req =
+        r#"{"action":"subscribe","bars":[],"quotes":[],"trades":[],"orderbooks":["BTC/USD"]}"#;
+      assert_eq!(
+        stream.next().await.unwrap()?,
+        Message::Text(sub_req.to_string()),
+      );
+      let sub_resp =
+        r#"[{"T":"subscription","trades":[],"quotes":[],"bars":[],"orderbooks":["BTC/USD"]}]"#;
+      stream.send(Message::Text(sub_resp.to_string())).await?;
+      let () = subscribed_recv.await.unwrap();
+
+      let update = r#"[{"T":"o","S":"BTC/USD","t":"2024-03-12T10:38:50Z","b":[{"p":71859.53,"s":0.27994}],"a":[]}]"#;
+      stream.send(Message::Text(update.to_string())).await?;
+      stream.send(Message::Close(None)).await?;
+      Ok(())
+    };
+
+    let (mut stream, mut subscription) = mock_stream::<RealtimeData<Crypto>, _, _>(test)
+      .await
+      .unwrap();
+
+    let mut data = MarketData::default();
+    data.set_orderbooks(["BTC/USD"]);
+
+    let subscribe = subscription.subscribe(&data).boxed_local();
+    let () = drive(subscribe, &mut stream)
+      .await
+      .unwrap()
+      .unwrap()
+      .unwrap();
+
+    assert_eq!(subscription.subscriptions().orderbooks, data.orderbooks);
+    let () = subscribed_send.send(()).unwrap();
+
+    let data = stream.next().await.unwrap().unwrap().unwrap();
+    let orderbook = match data {
+      Data::Orderbook(orderbook) => orderbook,
+      _ => panic!("received unexpected data: {data:?}"),
+    };
+    assert_eq!(orderbook.symbol, "BTC/USD");
+    assert_eq!(orderbook.bids.len(), 1);
+    assert!(orderbook.asks.is_empty());
+  }
+
   /// Check that we can adjust the current market data subscription on
   /// the fly.
   #[test(tokio::test)]
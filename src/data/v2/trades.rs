@@ -1,6 +1,8 @@
 // Copyright (C) 2022-2024 The apca Developers
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use std::collections::BTreeMap;
+
 use chrono::DateTime;
 use chrono::Utc;
 
@@ -10,8 +12,11 @@ use serde::Deserialize;
 use serde::Serialize;
 use serde_urlencoded::to_string as to_query;
 
+use crate::data;
 use crate::data::v2::Feed;
-use crate::data::DATA_BASE_URL;
+use crate::paginate::Paginated;
+use crate::util::map_from_str;
+use crate::util::string_slice_to_str;
 use crate::util::vec_from_str;
 use crate::Str;
 
@@ -135,7 +140,7 @@ Endpoint! {
   ]
 
   fn base_url() -> Option<Str> {
-    Some(DATA_BASE_URL.into())
+    data::base_url()
   }
 
   fn path(input: &Self::Input) -> Str {
@@ -147,6 +152,156 @@ Endpoint! {
   }
 }
 
+impl Paginated for List {
+  type Item = Trade;
+
+  #[inline]
+  fn set_page_token(input: &mut Self::Input, token: String) {
+    input.page_token = Some(token);
+  }
+
+  #[inline]
+  fn into_items(output: Self::Output) -> (Vec<Self::Item>, Option<String>) {
+    (output.trades, output.next_page_token)
+  }
+}
+
+
+/// A GET request to be issued to the /v2/stocks/trades endpoint,
+/// retrieving trades for multiple symbols at once.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ListMultiReq {
+  /// The symbols for which to retrieve market data.
+  #[serde(rename = "symbols", serialize_with = "string_slice_to_str")]
+  pub symbols: Vec<String>,
+  /// The maximum number of trades to be returned in total, across all
+  /// symbols.
+  ///
+  /// It can be between 1 and 10000. Defaults to 1000 if the provided
+  /// value is `None`.
+  #[serde(rename = "limit")]
+  pub limit: Option<usize>,
+  /// Filter trades equal to or after this time.
+  #[serde(rename = "start")]
+  pub start: DateTime<Utc>,
+  /// Filter trades equal to or before this time.
+  #[serde(rename = "end")]
+  pub end: DateTime<Utc>,
+  /// The data feed to use.
+  ///
+  /// Defaults to [`IEX`][Feed::IEX] for free users and
+  /// [`SIP`][Feed::SIP] for users with an unlimited subscription.
+  #[serde(rename = "feed")]
+  pub feed: Option<Feed>,
+  /// If provided we will pass a page token to continue where we left off.
+  #[serde(rename = "page_token", skip_serializing_if = "Option::is_none")]
+  pub page_token: Option<String>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  #[serde(skip)]
+  pub _non_exhaustive: (),
+}
+
+
+/// A helper for initializing [`ListMultiReq`] objects.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ListMultiReqInit {
+  /// See `ListMultiReq::limit`.
+  pub limit: Option<usize>,
+  /// See `ListMultiReq::feed`.
+  pub feed: Option<Feed>,
+  /// See `ListMultiReq::page_token`.
+  pub page_token: Option<String>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  pub _non_exhaustive: (),
+}
+
+impl ListMultiReqInit {
+  /// Create a [`ListMultiReq`] from a `ListMultiReqInit`.
+  #[inline]
+  pub fn init<I, S>(self, symbols: I, start: DateTime<Utc>, end: DateTime<Utc>) -> ListMultiReq
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    ListMultiReq {
+      symbols: symbols.into_iter().map(S::into).collect(),
+      start,
+      end,
+      limit: self.limit,
+      feed: self.feed,
+      page_token: self.page_token,
+      _non_exhaustive: (),
+    }
+  }
+}
+
+
+/// A collection of trades for multiple symbols as returned by the API.
+/// This is one page of trades.
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+pub struct MultiTrades {
+  /// The returned trades, keyed by symbol.
+  #[serde(rename = "trades", default, deserialize_with = "map_from_str")]
+  pub trades: BTreeMap<String, Vec<Trade>>,
+  /// The token to provide to a request to get the next page of trades
+  /// for this request.
+  #[serde(rename = "next_page_token")]
+  pub next_page_token: Option<String>,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  #[serde(skip)]
+  pub _non_exhaustive: (),
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the /v2/stocks/trades endpoint.
+  pub ListMulti(ListMultiReq),
+  Ok => MultiTrades, [
+    /// The market data was retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => ListMultiError, [
+    /// A query parameter was invalid.
+    /* 400 */ BAD_REQUEST => InvalidInput,
+  ]
+
+  fn base_url() -> Option<Str> {
+    data::base_url()
+  }
+
+  fn path(_input: &Self::Input) -> Str {
+    "/v2/stocks/trades".into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    Ok(Some(to_query(input)?.into()))
+  }
+}
+
+/// Pagination spans the entire set of symbols requested. Items are
+/// reported along with the symbol they belong to.
+impl Paginated for ListMulti {
+  type Item = (String, Trade);
+
+  #[inline]
+  fn set_page_token(input: &mut Self::Input, token: String) {
+    input.page_token = Some(token);
+  }
+
+  #[inline]
+  fn into_items(output: Self::Output) -> (Vec<Self::Item>, Option<String>) {
+    let items = output
+      .trades
+      .into_iter()
+      .flat_map(|(symbol, items)| items.into_iter().map(move |item| (symbol.clone(), item)))
+      .collect();
+    (items, output.next_page_token)
+  }
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -165,6 +320,35 @@ mod tests {
   use crate::RequestError;
 
 
+  /// Verify that we can properly parse a reference multi-symbol trades
+  /// response.
+  #[test]
+  fn parse_reference_multi_trades() {
+    let response = r#"{
+    "trades": {
+      "AAPL": [
+        {"t": "2021-02-06T13:04:56.334320128Z", "x": "C", "p": 136.6, "s": 100, "c": [" "], "i": 1, "z": "C"}
+      ],
+      "SPY": [
+        {"t": "2021-02-06T13:04:56.334320128Z", "x": "C", "p": 387.62, "s": 100, "c": [" "], "i": 2, "z": "B"},
+        {"t": "2021-02-06T13:09:42.325484032Z", "x": "C", "p": 387.69, "s": 200, "c": [" "], "i": 3, "z": "B"}
+      ]
+    },
+    "next_page_token": null
+}"#;
+
+    let res = from_json::<<ListMulti as Endpoint>::Output>(response).unwrap();
+    assert_eq!(res.trades.len(), 2);
+    assert_eq!(res.trades["AAPL"][0].price, Num::new(1366, 10));
+    assert_eq!(res.trades["SPY"][1].size, 200);
+    assert_eq!(res.next_page_token, None);
+
+    let (items, token) = ListMulti::into_items(res);
+    assert_eq!(items.len(), 3);
+    assert_eq!(items[2].0, "SPY");
+    assert_eq!(token, None);
+  }
+
   /// Verify that we can properly parse a reference trades response.
   #[test]
   fn parse_reference_trades() {
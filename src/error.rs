@@ -1,6 +1,8 @@
 // Copyright (C) 2019-2024 The apca Developers
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use std::convert::Infallible;
+use std::error::Error as StdError;
 use std::fmt::Debug;
 use std::fmt::Display;
 use std::fmt::Formatter;
@@ -16,6 +18,7 @@ use thiserror::Error;
 use url::ParseError;
 use websocket_util::tungstenite::Error as WebSocketError;
 
+use crate::meta::ResponseMeta;
 use crate::Str;
 
 
@@ -46,6 +49,88 @@ pub enum RequestError<E> {
     #[source]
     IoError,
   ),
+  /// The request timed out.
+  ///
+  /// This variant covers timeouts while connecting, while waiting for
+  /// the response, and while receiving the response body.
+  #[error("the request timed out")]
+  Timeout,
+  /// The response was encoded using a content encoding that is not
+  /// supported, possibly because the corresponding feature is not
+  /// enabled.
+  #[error("the response uses unsupported content encoding `{0}`")]
+  UnsupportedEncoding(String),
+  /// The response body exceeded the configured maximum size (in
+  /// bytes).
+  #[error("the response body exceeds the maximum size of {0} bytes")]
+  BodyTooLarge(usize),
+  /// The request would modify orders or positions in the live trading
+  /// environment, but live trading was not enabled on the client
+  /// [builder][crate::Client::builder].
+  #[error("refusing to modify orders or positions in the live trading environment")]
+  LiveTradingDisabled,
+  /// The credentials to use could not be retrieved from the
+  /// [`CredentialsProvider`][crate::CredentialsProvider].
+  #[error("failed to retrieve credentials")]
+  Credentials(#[source] Box<dyn StdError + Send + Sync>),
+  /// An error reported by a [`Middleware`][crate::Middleware], causing
+  /// the request to be aborted.
+  #[error("the request was aborted by middleware")]
+  Middleware(#[source] Box<dyn StdError + Send + Sync>),
+  /// An error reported by a custom [`Transport`][crate::Transport].
+  #[error("the transport reported an error")]
+  Transport(#[source] Box<dyn StdError + Send + Sync>),
+}
+
+impl RequestError<Infallible> {
+  /// Convert a transport level error into a request error for an
+  /// arbitrary endpoint.
+  pub(crate) fn into_request_error<E>(self) -> RequestError<E> {
+    match self {
+      Self::Endpoint(infallible) => match infallible {},
+      Self::Hyper(err) => RequestError::Hyper(err),
+      Self::HyperUtil(err) => RequestError::HyperUtil(err),
+      Self::Io(err) => RequestError::Io(err),
+      Self::Timeout => RequestError::Timeout,
+      Self::UnsupportedEncoding(encoding) => RequestError::UnsupportedEncoding(encoding),
+      Self::BodyTooLarge(limit) => RequestError::BodyTooLarge(limit),
+      Self::LiveTradingDisabled => RequestError::LiveTradingDisabled,
+      Self::Credentials(err) => RequestError::Credentials(err),
+      Self::Middleware(err) => RequestError::Middleware(err),
+      Self::Transport(err) => RequestError::Transport(err),
+    }
+  }
+}
+
+
+/// A [`RequestError`] along with metadata of the response that caused
+/// it, if a response was received at all.
+#[derive(Debug, Error)]
+#[error("failed to issue request")]
+pub struct RequestErrorWithMeta<E> {
+  /// The actual error.
+  #[source]
+  pub error: RequestError<E>,
+  /// Metadata of the response received from the server, if any.
+  pub meta: Option<ResponseMeta>,
+}
+
+impl<E> RequestErrorWithMeta<E> {
+  /// Retrieve the ID Alpaca assigned to the request, if known.
+  #[inline]
+  pub fn request_id(&self) -> Option<&str> {
+    self
+      .meta
+      .as_ref()
+      .and_then(|meta| meta.request_id.as_deref())
+  }
+}
+
+impl<E> From<RequestError<E>> for RequestErrorWithMeta<E> {
+  #[inline]
+  fn from(error: RequestError<E>) -> Self {
+    Self { error, meta: None }
+  }
 }
 
 
@@ -86,6 +86,24 @@
 //! println!("buying power:\t{} {currency}", account.buying_power);
 //! # })
 //! ```
+//!
+//! Connections are secured using the system's native TLS
+//! implementation, via the default enabled `native-tls` feature, or
+//! using `rustls`, via the `rustls` feature. One of these TLS backend
+//! features is required: when building with `--no-default-features`,
+//! make sure to enable one of them explicitly, e.g.,
+//! `--no-default-features --features=rustls`.
+//!
+//! With the `metrics` feature enabled, the crate reports statistics via
+//! the [`metrics`](https://crates.io/crates/metrics) crate:
+//! - `apca_requests_total` and `apca_request_duration_seconds` count
+//!   and time requests issued via [`Client::issue`], labeled with
+//!   `endpoint`, `method`, and `status` (class)
+//! - `apca_stream_connects_total` counts (re-)connects of streams,
+//!   labeled with `stream`
+//! - `apca_stream_messages_total` and `apca_stream_parse_errors_total`
+//!   count messages received over streams, labeled with `stream` and
+//!   (for the former) `kind`
 
 #[macro_use]
 extern crate http_endpoint;
@@ -100,20 +118,62 @@ pub mod api;
 /// A module for retrieving market data.
 pub mod data;
 
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
 mod api_info;
+mod cassette;
 mod client;
+mod compression;
+mod credentials;
 mod error;
+mod meta;
+mod middleware;
+mod paginate;
+mod proxy;
+mod rate_limit;
+mod retry;
+mod secret;
 mod subscribable;
+mod telemetry;
+mod tls;
+mod transport;
 mod util;
 mod websocket;
 
 use std::borrow::Cow;
 
 pub use crate::api_info::ApiInfo;
+pub use crate::api_info::Environment;
+pub use crate::cassette::Recorder;
+pub use crate::cassette::Replayer;
 pub use crate::client::Client;
+pub use crate::credentials::Credentials;
+pub use crate::credentials::CredentialsProvider;
+pub use crate::credentials::EnvCredentials;
+pub use crate::credentials::FileCredentials;
 pub use crate::endpoint::ApiError;
 pub use crate::error::Error;
 pub use crate::error::RequestError;
+pub use crate::error::RequestErrorWithMeta;
+pub use crate::meta::ResponseMeta;
+pub use crate::middleware::Middleware;
+pub use crate::paginate::Paginate;
+pub use crate::paginate::Paginated;
+pub use crate::paginate::PaginationError;
+pub use crate::proxy::Proxy;
+pub use crate::rate_limit::RateLimiter;
+pub use crate::retry::RetryPolicy;
+pub use crate::secret::Secret;
 pub use crate::subscribable::Subscribable;
+pub use crate::transport::HyperTransport;
+pub use crate::transport::Transport;
+pub use crate::transport::TransportBody;
+pub use crate::transport::TransportError;
+pub use crate::websocket::StreamConfig;
+
+/// The `rustls` crate, as used for securing connections.
+#[cfg(feature = "rustls")]
+pub use rustls;
 
 type Str = Cow<'static, str>;
@@ -0,0 +1,137 @@
+// Copyright (C) 2024 The apca Developers
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::str::FromStr;
+
+use chrono::DateTime;
+use chrono::TimeZone as _;
+use chrono::Utc;
+
+use http::response::Parts;
+use http::HeaderMap;
+use http::StatusCode;
+
+use crate::rate_limit::HDR_RATE_LIMIT_REMAINING;
+use crate::retry::HDR_RATE_LIMIT_RESET;
+
+
+/// The HTTP header containing the ID Alpaca assigned to a request.
+pub(crate) const HDR_REQUEST_ID: &str = "X-Request-ID";
+/// The HTTP header conveying the number of requests permitted per
+/// rate limit window.
+pub(crate) const HDR_RATE_LIMIT_LIMIT: &str = "X-RateLimit-Limit";
+
+
+/// Parse the value of the header with the given name, if present.
+fn parse_header<T>(headers: &HeaderMap, name: &str) -> Option<T>
+where
+  T: FromStr,
+{
+  headers
+    .get(name)
+    .and_then(|value| value.to_str().ok())
+    .and_then(|value| value.trim().parse::<T>().ok())
+}
+
+
+/// Metadata about a response received from the server.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ResponseMeta {
+  /// The HTTP status code of the response.
+  pub status: StatusCode,
+  /// The ID Alpaca assigned to the request, as reported in the
+  /// `X-Request-ID` header.
+  ///
+  /// This ID should be provided when contacting Alpaca support about a
+  /// particular request.
+  pub request_id: Option<String>,
+  /// The number of requests permitted per rate limit window, as
+  /// reported in the `X-RateLimit-Limit` header.
+  pub rate_limit_limit: Option<u32>,
+  /// The number of requests remaining in the current rate limit
+  /// window, as reported in the `X-RateLimit-Remaining` header.
+  pub rate_limit_remaining: Option<u32>,
+  /// The point in time at which the current rate limit window resets,
+  /// as reported in the `X-RateLimit-Reset` header.
+  pub rate_limit_reset: Option<DateTime<Utc>>,
+  /// The full set of response headers.
+  pub headers: HeaderMap,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  pub _non_exhaustive: (),
+}
+
+impl From<&Parts> for ResponseMeta {
+  fn from(parts: &Parts) -> Self {
+    let headers = &parts.headers;
+
+    Self {
+      status: parts.status,
+      request_id: parse_header(headers, HDR_REQUEST_ID),
+      rate_limit_limit: parse_header(headers, HDR_RATE_LIMIT_LIMIT),
+      rate_limit_remaining: parse_header(headers, HDR_RATE_LIMIT_REMAINING),
+      rate_limit_reset: parse_header(headers, HDR_RATE_LIMIT_RESET)
+        .and_then(|timestamp| Utc.timestamp_opt(timestamp, 0).single()),
+      headers: headers.clone(),
+      _non_exhaustive: (),
+    }
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use http::Response;
+
+
+  /// Check that we can extract metadata from a response.
+  #[test]
+  fn meta_from_response() {
+    let response = Response::builder()
+      .status(StatusCode::CREATED)
+      .header(HDR_REQUEST_ID, "1d3a9c5b2f7e4a60b8d2c4e6f8a0b1c3")
+      .header(HDR_RATE_LIMIT_LIMIT, "200")
+      .header(HDR_RATE_LIMIT_REMAINING, "199")
+      .header(HDR_RATE_LIMIT_RESET, "1704164645")
+      .body(())
+      .unwrap();
+    let (parts, ()) = response.into_parts();
+
+    let meta = ResponseMeta::from(&parts);
+    assert_eq!(meta.status, StatusCode::CREATED);
+    assert_eq!(
+      meta.request_id.as_deref(),
+      Some("1d3a9c5b2f7e4a60b8d2c4e6f8a0b1c3")
+    );
+    assert_eq!(meta.rate_limit_limit, Some(200));
+    assert_eq!(meta.rate_limit_remaining, Some(199));
+    assert_eq!(
+      meta.rate_limit_reset,
+      Some(
+        DateTime::parse_from_rfc3339("2024-01-02T03:04:05Z")
+          .unwrap()
+          .with_timezone(&Utc)
+      )
+    );
+    assert_eq!(meta.headers.len(), 4);
+  }
+
+  /// Check that missing or malformed headers are handled gracefully.
+  #[test]
+  fn meta_without_headers() {
+    let response = Response::builder()
+      .header(HDR_RATE_LIMIT_REMAINING, "lots")
+      .body(())
+      .unwrap();
+    let (parts, ()) = response.into_parts();
+
+    let meta = ResponseMeta::from(&parts);
+    assert_eq!(meta.status, StatusCode::OK);
+    assert_eq!(meta.request_id, None);
+    assert_eq!(meta.rate_limit_limit, None);
+    assert_eq!(meta.rate_limit_remaining, None);
+    assert_eq!(meta.rate_limit_reset, None);
+  }
+}
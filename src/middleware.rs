@@ -0,0 +1,194 @@
+// Copyright (C) 2024 The apca Developers
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::error::Error as StdError;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use http::request;
+use http::response;
+use http::Request;
+use http_body_util::Full;
+
+use hyper::body::Bytes;
+
+use crate::transport::TransportError;
+
+
+/// A trait for hooking into the exchanges a [`Client`][crate::Client]
+/// performs with the server.
+///
+/// Middleware is registered via
+/// [`Client::builder`][crate::Client::builder] and is invoked for
+/// every attempt at sending a request, i.e., multiple times for a
+/// request that is retried. Hooks of multiple middleware objects are
+/// run in the order in which they were registered for requests and in
+/// reverse order for responses and errors.
+///
+/// Per-request state, such as the time a request was sent, can be
+/// stored in the request's extensions, which are made available to the
+/// response and error hooks.
+pub trait Middleware: Debug + Send + Sync {
+  /// Inspect and possibly modify a request before it is sent.
+  ///
+  /// Returning an error aborts the request, which then fails with
+  /// [`RequestError::Middleware`][crate::RequestError::Middleware].
+  #[inline]
+  fn on_request(
+    &self,
+    _request: &mut Request<Full<Bytes>>,
+  ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+    Ok(())
+  }
+
+  /// Observe a response, consisting of its head and its (decoded)
+  /// body, before it is evaluated by the endpoint.
+  #[inline]
+  fn on_response(&self, _request: &request::Parts, _response: &response::Parts, _body: &Bytes) {}
+
+  /// Observe an error that prevented a (complete) response from being
+  /// received.
+  #[inline]
+  fn on_error(&self, _request: &request::Parts, _error: &TransportError) {}
+}
+
+impl<M> Middleware for Arc<M>
+where
+  M: Middleware + ?Sized,
+{
+  #[inline]
+  fn on_request(
+    &self,
+    request: &mut Request<Full<Bytes>>,
+  ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+    self.as_ref().on_request(request)
+  }
+
+  #[inline]
+  fn on_response(&self, request: &request::Parts, response: &response::Parts, body: &Bytes) {
+    self.as_ref().on_response(request, response, body)
+  }
+
+  #[inline]
+  fn on_error(&self, request: &request::Parts, error: &TransportError) {
+    self.as_ref().on_error(request, error)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::sync::Mutex;
+  use std::time::Duration;
+  use std::time::Instant;
+
+  use http::HeaderValue;
+  use http::StatusCode;
+
+  use test_log::test;
+
+  use crate::api::v2::clock;
+  use crate::transport::test::response;
+  use crate::transport::test::Canned;
+  use crate::ApiInfo;
+  use crate::Client;
+  use crate::RequestError;
+
+
+  /// A middleware adding a header to each request and recording the
+  /// responses it observed.
+  #[derive(Debug, Default)]
+  struct Recording {
+    /// The status, body, and latency of each response observed.
+    responses: Mutex<Vec<(StatusCode, Bytes, Duration)>>,
+  }
+
+  impl Middleware for Recording {
+    fn on_request(
+      &self,
+      request: &mut Request<Full<Bytes>>,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+      let _prev = request
+        .headers_mut()
+        .insert("X-Audit", HeaderValue::from_static("1"));
+      let _prev = request.extensions_mut().insert(Instant::now());
+      Ok(())
+    }
+
+    fn on_response(&self, request: &request::Parts, response: &response::Parts, body: &Bytes) {
+      let latency = request.extensions.get::<Instant>().unwrap().elapsed();
+      let () = self
+        .responses
+        .lock()
+        .unwrap()
+        .push((response.status, body.clone(), latency));
+    }
+  }
+
+  /// A middleware rejecting all requests.
+  #[derive(Debug)]
+  struct Rejecting;
+
+  impl Middleware for Rejecting {
+    fn on_request(
+      &self,
+      _request: &mut Request<Full<Bytes>>,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+      Err("circuit open".into())
+    }
+  }
+
+
+  /// Check that middleware can modify requests and observe responses.
+  #[test(tokio::test)]
+  async fn modify_and_observe() {
+    let body = r#"{
+  "timestamp": "2018-04-01T12:00:00.000Z",
+  "is_open": true,
+  "next_open": "2018-04-01T12:00:00.000Z",
+  "next_close": "2018-04-01T12:00:00.000Z"
+}"#;
+    let api_info = ApiInfo::from_parts("https://example.com", "key-id", "secret").unwrap();
+    let transport = Canned::new([response(StatusCode::OK, body)]);
+    let recording = Arc::new(Recording::default());
+    let client = Client::builder()
+      .transport(transport.clone())
+      .middleware(recording.clone())
+      .build(api_info);
+
+    let clock = client.issue::<clock::Get>(&()).await.unwrap();
+    assert!(clock.open);
+
+    let requests = transport.requests.lock().unwrap();
+    assert_eq!(requests[0].headers().get("X-Audit").unwrap(), "1");
+
+    let responses = recording.responses.lock().unwrap();
+    assert_eq!(responses.len(), 1);
+    assert_eq!(responses[0].0, StatusCode::OK);
+    assert_eq!(responses[0].1, body);
+  }
+
+  /// Check that middleware can reject requests.
+  #[test(tokio::test)]
+  async fn reject_request() {
+    let api_info = ApiInfo::from_parts("https://example.com", "key-id", "secret").unwrap();
+    let transport = Canned::new([]);
+    let recording = Arc::new(Recording::default());
+    let client = Client::builder()
+      .transport(transport.clone())
+      .middleware(recording.clone())
+      .middleware(Rejecting)
+      .build(api_info);
+
+    let err = client.issue::<clock::Get>(&()).await.unwrap_err();
+    match err {
+      RequestError::Middleware(err) => assert_eq!(err.to_string(), "circuit open"),
+      _ => panic!("Received unexpected error: {err:?}"),
+    }
+
+    assert!(transport.requests.lock().unwrap().is_empty());
+    assert!(recording.responses.lock().unwrap().is_empty());
+  }
+}
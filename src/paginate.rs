@@ -0,0 +1,358 @@
+// Copyright (C) 2024 The apca Developers
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
+
+use futures::future::BoxFuture;
+use futures::Stream;
+
+use http_endpoint::Endpoint;
+
+use thiserror::Error;
+
+use crate::Client;
+use crate::RequestError;
+
+
+/// A trait for endpoints reporting results in the form of pages of
+/// individual items.
+///
+/// Such an endpoint can be used with [`Client::paginate`] to retrieve
+/// all items in the form of a single stream.
+pub trait Paginated: Endpoint {
+  /// The type of the individual items reported.
+  type Item;
+
+  /// Set the token identifying the page to retrieve as part of the
+  /// provided request.
+  fn set_page_token(input: &mut Self::Input, token: String);
+
+  /// Split a page into the items it contains and the token identifying
+  /// the next page, if any.
+  fn into_items(output: Self::Output) -> (Vec<Self::Item>, Option<String>);
+}
+
+
+/// An error encountered while retrieving a page of items via
+/// [`Client::paginate`].
+#[derive(Debug, Error)]
+#[error("failed to retrieve page {page}")]
+pub struct PaginationError<E> {
+  /// The zero-based index of the page that could not be retrieved.
+  pub page: usize,
+  /// The actual error.
+  #[source]
+  pub error: RequestError<E>,
+}
+
+
+/// The type of future used for retrieving a single page.
+type PageFuture<'c, R> =
+  BoxFuture<'c, Result<<R as Endpoint>::Output, RequestError<<R as Endpoint>::Error>>>;
+
+
+/// A stream over the items reported by a [`Paginated`] endpoint, as
+/// created by [`Client::paginate`].
+///
+/// Pages are retrieved lazily, as items are requested. Once an error
+/// is reported, the stream ends.
+#[must_use = "streams do nothing unless polled"]
+pub struct Paginate<'c, R>
+where
+  R: Paginated,
+{
+  /// The client used for issuing requests.
+  client: &'c Client,
+  /// The request to issue for retrieving the next page.
+  input: R::Input,
+  /// The zero-based index of the page retrieved next.
+  page: usize,
+  /// Whether there are more pages to retrieve.
+  more: bool,
+  /// Whether to retrieve the next page while items of the current one
+  /// are still being consumed.
+  prefetch: bool,
+  /// The items retrieved but not yet yielded.
+  items: VecDeque<R::Item>,
+  /// The retrieval of the next page, if in progress.
+  pending: Option<PageFuture<'c, R>>,
+  /// An error to report once all previously retrieved items have been
+  /// yielded.
+  error: Option<PaginationError<R::Error>>,
+}
+
+impl<'c, R> Paginate<'c, R>
+where
+  R: Paginated,
+{
+  /// Create a new `Paginate` object for retrieving items using the
+  /// provided request.
+  pub(crate) fn new(client: &'c Client, input: R::Input) -> Self {
+    Self {
+      client,
+      input,
+      page: 0,
+      more: true,
+      prefetch: false,
+      items: VecDeque::new(),
+      pending: None,
+      error: None,
+    }
+  }
+
+  /// Enable or disable prefetching of pages.
+  ///
+  /// With prefetching enabled, retrieval of the next page starts as
+  /// soon as the previous one has been received, instead of only once
+  /// all of its items were consumed. Note that the retrieval only
+  /// makes progress while the stream is polled.
+  pub fn prefetch(mut self, enable: bool) -> Self {
+    self.prefetch = enable;
+    self
+  }
+}
+
+impl<R> Debug for Paginate<'_, R>
+where
+  R: Paginated,
+  R::Input: Debug,
+{
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    fmt
+      .debug_struct("Paginate")
+      .field("input", &self.input)
+      .field("page", &self.page)
+      .field("more", &self.more)
+      .field("prefetch", &self.prefetch)
+      .field("items", &self.items.len())
+      .field("pending", &self.pending.is_some())
+      .finish()
+  }
+}
+
+// We never pin any of our members (the page future is boxed), so we
+// can be `Unpin` irrespective of the endpoint's types.
+impl<R> Unpin for Paginate<'_, R> where R: Paginated {}
+
+impl<'c, R> Stream for Paginate<'c, R>
+where
+  R: Paginated + 'c,
+  R::Output: Send,
+  R::Error: Send,
+{
+  type Item = Result<R::Item, PaginationError<R::Error>>;
+
+  fn poll_next(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+    let this = self.get_mut();
+
+    loop {
+      if this.pending.is_none()
+        && this.error.is_none()
+        && this.more
+        && (this.items.is_empty() || this.prefetch)
+      {
+        this.pending = Some(Box::pin(this.client.issue::<R>(&this.input)));
+      }
+
+      if let Some(pending) = &mut this.pending {
+        if let Poll::Ready(result) = pending.as_mut().poll(ctx) {
+          this.pending = None;
+
+          match result {
+            Ok(output) => {
+              let (items, token) = R::into_items(output);
+              let () = this.items.extend(items);
+              this.page += 1;
+
+              match token {
+                Some(token) => R::set_page_token(&mut this.input, token),
+                None => this.more = false,
+              }
+            },
+            Err(error) => {
+              this.more = false;
+              this.error = Some(PaginationError {
+                page: this.page,
+                error,
+              });
+            },
+          }
+          continue
+        }
+      }
+
+      if let Some(item) = this.items.pop_front() {
+        break Poll::Ready(Some(Ok(item)))
+      }
+
+      if let Some(error) = this.error.take() {
+        break Poll::Ready(Some(Err(error)))
+      }
+
+      if this.pending.is_some() {
+        break Poll::Pending
+      }
+
+      break Poll::Ready(None)
+    }
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::str::FromStr as _;
+  use std::sync::Arc;
+
+  use chrono::DateTime;
+
+  use futures::TryStreamExt as _;
+
+  use http::StatusCode;
+
+  use test_log::test;
+
+  use crate::data::v2::trades::List;
+  use crate::data::v2::trades::ListReq;
+  use crate::data::v2::trades::ListReqInit;
+  use crate::transport::test::response;
+  use crate::transport::test::Canned;
+  use crate::ApiInfo;
+
+
+  const PAGE1: &str = r#"{
+  "trades": [
+    {"t": "2021-02-06T13:04:56.334320128Z", "x": "C", "p": 387.62, "s": 100, "c": [" "], "i": 1, "z": "B"},
+    {"t": "2021-02-06T13:09:42.325484032Z", "x": "C", "p": 387.69, "s": 100, "c": [" "], "i": 2, "z": "B"}
+  ],
+  "symbol": "SPY",
+  "next_page_token": "token1"
+}"#;
+  const PAGE2: &str = r#"{
+  "trades": [],
+  "symbol": "SPY",
+  "next_page_token": "token2"
+}"#;
+  const PAGE3: &str = r#"{
+  "trades": [
+    {"t": "2021-02-06T13:10:00.000000000Z", "x": "C", "p": 387.70, "s": 200, "c": [" "], "i": 3, "z": "B"}
+  ],
+  "symbol": "SPY",
+  "next_page_token": null
+}"#;
+
+
+  /// Create a `ListReq` for retrieving trades.
+  fn request() -> ListReq {
+    let start = DateTime::from_str("2021-02-06T13:00:00Z").unwrap();
+    let end = DateTime::from_str("2021-02-06T14:00:00Z").unwrap();
+    ListReqInit::default().init("SPY", start, end)
+  }
+
+  /// Create a `Client` using the provided canned transport.
+  fn canned_client(transport: &Arc<Canned>) -> Client {
+    let api_info = ApiInfo::from_parts("https://example.com", "key-id", "secret").unwrap();
+    Client::builder()
+      .transport(Arc::clone(transport))
+      .build(api_info)
+  }
+
+  /// Retrieve the page tokens of the requests issued.
+  fn page_tokens(transport: &Canned) -> Vec<Option<String>> {
+    transport
+      .requests
+      .lock()
+      .unwrap()
+      .iter()
+      .map(|request| {
+        request.uri().query().and_then(|query| {
+          query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("page_token="))
+            .map(str::to_string)
+        })
+      })
+      .collect()
+  }
+
+
+  /// Check that we can retrieve the items of all pages.
+  #[test(tokio::test)]
+  async fn paginate_all() {
+    let transport = Canned::new([
+      response(StatusCode::OK, PAGE1),
+      response(StatusCode::OK, PAGE2),
+      response(StatusCode::OK, PAGE3),
+    ]);
+    let client = canned_client(&transport);
+
+    let trades = client
+      .paginate::<List>(request())
+      .try_collect::<Vec<_>>()
+      .await
+      .unwrap();
+    let sizes = trades.iter().map(|trade| trade.size).collect::<Vec<_>>();
+    assert_eq!(sizes, vec![100, 100, 200]);
+    assert_eq!(
+      page_tokens(&transport),
+      vec![None, Some("token1".to_string()), Some("token2".to_string())]
+    );
+  }
+
+  /// Check that pages are retrieved lazily without prefetching and
+  /// ahead of time with it.
+  #[test(tokio::test)]
+  async fn paginate_prefetch() {
+    let transport = Canned::new([response(StatusCode::OK, PAGE1)]);
+    let client = canned_client(&transport);
+
+    let mut stream = client.paginate::<List>(request());
+    let _trade = stream.try_next().await.unwrap().unwrap();
+    let _trade = stream.try_next().await.unwrap().unwrap();
+    assert_eq!(transport.requests.lock().unwrap().len(), 1);
+
+    let transport = Canned::new([
+      response(StatusCode::OK, PAGE1),
+      response(StatusCode::OK, PAGE3),
+    ]);
+    let client = canned_client(&transport);
+
+    let mut stream = client.paginate::<List>(request()).prefetch(true);
+    let _trade = stream.try_next().await.unwrap().unwrap();
+    assert_eq!(transport.requests.lock().unwrap().len(), 2);
+
+    let trades = stream.try_collect::<Vec<_>>().await.unwrap();
+    assert_eq!(trades.len(), 2);
+  }
+
+  /// Check that we report the page that failed to be retrieved.
+  #[test(tokio::test)]
+  async fn paginate_error() {
+    let transport = Canned::new([
+      response(StatusCode::OK, PAGE1),
+      response(StatusCode::OK, PAGE2),
+      response(StatusCode::BAD_REQUEST, ""),
+    ]);
+    let client = canned_client(&transport);
+
+    let mut stream = client.paginate::<List>(request()).prefetch(true);
+    let _trade = stream.try_next().await.unwrap().unwrap();
+    let _trade = stream.try_next().await.unwrap().unwrap();
+    let err = stream.try_next().await.unwrap_err();
+    assert_eq!(err.page, 2);
+    assert!(
+      matches!(err.error, RequestError::Endpoint(..)),
+      "{:?}",
+      err.error
+    );
+    assert!(stream.try_next().await.unwrap().is_none());
+  }
+}
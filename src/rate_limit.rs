@@ -0,0 +1,246 @@
+// Copyright (C) 2024 The apca Developers
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::any::type_name;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use http::HeaderMap;
+use http_endpoint::Endpoint;
+
+use tokio::time::sleep;
+
+use tracing::debug;
+
+
+/// The HTTP header conveying the number of requests remaining in the
+/// current rate limit window.
+pub(crate) const HDR_RATE_LIMIT_REMAINING: &str = "X-RateLimit-Remaining";
+
+
+/// The state of a token bucket.
+#[derive(Debug)]
+struct State {
+  /// The maximum number of tokens the bucket can hold.
+  capacity: f64,
+  /// The number of tokens currently available.
+  tokens: f64,
+  /// The number of tokens added to the bucket per second.
+  rate: f64,
+  /// The last time the bucket was refilled.
+  refilled: Instant,
+  /// Weights of individual endpoints, keyed by the endpoint's type
+  /// name.
+  weights: HashMap<&'static str, u32>,
+}
+
+impl State {
+  /// Refill the bucket based on the time elapsed since the last
+  /// refill.
+  fn refill(&mut self, now: Instant) {
+    let elapsed = now.saturating_duration_since(self.refilled);
+    self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate).min(self.capacity);
+    self.refilled = now;
+  }
+
+  /// Retrieve the weight of the endpoint with the given name.
+  fn weight(&self, endpoint: &str) -> f64 {
+    let weight = self.weights.get(endpoint).copied().unwrap_or(1);
+    // A weight exceeding the bucket's capacity could never be
+    // satisfied.
+    f64::from(weight).min(self.capacity)
+  }
+}
+
+
+/// A client side rate limiter based on the token bucket algorithm.
+///
+/// Each request issued through a [`Client`][crate::Client] using the
+/// rate limiter consumes a number of tokens (one, unless a different
+/// weight was configured for the endpoint in question) and waits for
+/// tokens to become available, if necessary. Tokens are replenished
+/// continuously.
+///
+/// The object is cheap to clone, and clones share the same underlying
+/// state. That makes it possible to share a rate limit between
+/// multiple clients accessing the same account. By default, the limiter
+/// allows for 200 requests per minute, which is the limit Alpaca
+/// imposes on a per-account basis.
+///
+/// The limiter also adjusts its state based on the
+/// `X-RateLimit-Remaining` header reported by the server, so that it
+/// accounts for requests issued through other means.
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+  state: Arc<Mutex<State>>,
+}
+
+impl RateLimiter {
+  /// Create a new `RateLimiter` allowing for `requests` requests to be
+  /// made per `period`.
+  ///
+  /// # Panics
+  /// This constructor panics if `requests` is zero or if `period` is
+  /// empty.
+  pub fn new(requests: u32, period: Duration) -> Self {
+    assert!(requests > 0, "rate limiter needs to allow for requests");
+    assert!(!period.is_zero(), "rate limiter period must not be empty");
+
+    let capacity = f64::from(requests);
+    let state = State {
+      capacity,
+      tokens: capacity,
+      rate: capacity / period.as_secs_f64(),
+      refilled: Instant::now(),
+      weights: HashMap::new(),
+    };
+
+    Self {
+      state: Arc::new(Mutex::new(state)),
+    }
+  }
+
+  /// Set the weight, i.e., the number of tokens consumed per request,
+  /// of the endpoint `R`.
+  ///
+  /// The weight is shared with all clones of the object.
+  pub fn with_weight<R>(self, weight: u32) -> Self
+  where
+    R: Endpoint,
+  {
+    let _prev = self
+      .state
+      .lock()
+      .unwrap()
+      .weights
+      .insert(type_name::<R>(), weight);
+    self
+  }
+
+  /// Try acquiring the tokens required for a request to the given
+  /// endpoint, returning the time to wait until enough tokens are
+  /// available if that is not currently the case.
+  fn try_acquire(&self, endpoint: &str, now: Instant) -> Result<(), Duration> {
+    let mut state = self.state.lock().unwrap();
+    let () = state.refill(now);
+
+    let weight = state.weight(endpoint);
+    if state.tokens >= weight {
+      state.tokens -= weight;
+      Ok(())
+    } else {
+      let missing = weight - state.tokens;
+      Err(Duration::from_secs_f64(missing / state.rate))
+    }
+  }
+
+  /// Wait until a request to the endpoint with the given (type) name
+  /// can be made.
+  pub(crate) async fn acquire(&self, endpoint: &str) {
+    while let Err(delay) = self.try_acquire(endpoint, Instant::now()) {
+      debug!(delay = debug(&delay), "waiting for rate limiter");
+      let () = sleep(delay).await;
+    }
+  }
+
+  /// Update the limiter's state based on the rate limit related
+  /// headers reported by the server.
+  pub(crate) fn update(&self, headers: &HeaderMap) {
+    let remaining = headers
+      .get(HDR_RATE_LIMIT_REMAINING)
+      .and_then(|value| value.to_str().ok())
+      .and_then(|value| value.trim().parse::<u32>().ok());
+
+    if let Some(remaining) = remaining {
+      let mut state = self.state.lock().unwrap();
+      let () = state.refill(Instant::now());
+      state.tokens = state.tokens.min(f64::from(remaining));
+    }
+  }
+}
+
+impl Default for RateLimiter {
+  #[inline]
+  fn default() -> Self {
+    Self::new(200, Duration::from_secs(60))
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use http::HeaderValue;
+
+  use test_log::test;
+
+  use crate::api::v2::account;
+  use crate::api::v2::orders;
+
+
+  /// Check that tokens are consumed and replenished as expected.
+  #[test]
+  fn acquire_and_refill() {
+    let limiter = RateLimiter::new(2, Duration::from_secs(1));
+    let now = Instant::now();
+    let endpoint = type_name::<account::Get>();
+
+    assert_eq!(limiter.try_acquire(endpoint, now), Ok(()));
+    assert_eq!(limiter.try_acquire(endpoint, now), Ok(()));
+    let delay = limiter.try_acquire(endpoint, now).unwrap_err();
+    assert!(delay <= Duration::from_millis(500), "{delay:?}");
+
+    let later = now + Duration::from_millis(500);
+    assert_eq!(limiter.try_acquire(endpoint, later), Ok(()));
+  }
+
+  /// Check that endpoint weights are honored and shared between clones.
+  #[test]
+  fn weighted_endpoints() {
+    let limiter = RateLimiter::new(10, Duration::from_secs(10));
+    let clone = limiter.clone().with_weight::<orders::List>(6);
+    let now = Instant::now();
+
+    assert_eq!(clone.try_acquire(type_name::<orders::List>(), now), Ok(()));
+    let delay = limiter
+      .try_acquire(type_name::<orders::List>(), now)
+      .unwrap_err();
+    assert!(delay <= Duration::from_secs(2), "{delay:?}");
+    assert_eq!(
+      limiter.try_acquire(type_name::<account::Get>(), now),
+      Ok(())
+    );
+  }
+
+  /// Check that the state is adjusted based on the reported number of
+  /// remaining requests.
+  #[test]
+  fn update_from_headers() {
+    let limiter = RateLimiter::new(100, Duration::from_secs(3600));
+    let endpoint = type_name::<account::Get>();
+
+    let mut headers = HeaderMap::new();
+    let _prev = headers.insert(HDR_RATE_LIMIT_REMAINING, HeaderValue::from(1));
+    let () = limiter.update(&headers);
+
+    let now = Instant::now();
+    assert_eq!(limiter.try_acquire(endpoint, now), Ok(()));
+    assert!(limiter.try_acquire(endpoint, now).is_err());
+  }
+
+  /// Check that acquiring tokens waits for them to become available.
+  #[test(tokio::test)]
+  async fn acquire_waits() {
+    let limiter = RateLimiter::new(1, Duration::from_millis(100));
+    let endpoint = type_name::<account::Get>();
+
+    let start = Instant::now();
+    let () = limiter.acquire(endpoint).await;
+    let () = limiter.acquire(endpoint).await;
+    assert!(start.elapsed() >= Duration::from_millis(90));
+  }
+}
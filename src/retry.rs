@@ -0,0 +1,393 @@
+// Copyright (C) 2024 The apca Developers
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::collections::hash_map::RandomState;
+use std::error::Error as StdError;
+use std::hash::BuildHasher as _;
+use std::hash::Hasher as _;
+use std::io::Error as IoError;
+use std::io::ErrorKind;
+use std::time::Duration;
+
+use chrono::DateTime;
+use chrono::TimeZone as _;
+use chrono::Utc;
+
+use http::header::RETRY_AFTER;
+use http::HeaderMap;
+use http::Method;
+use http::Request;
+use http::StatusCode;
+use http_body_util::BodyExt as _;
+use http_body_util::Full;
+
+use hyper::body::Bytes;
+use hyper::Error as HyperError;
+
+use serde_json::from_slice as json_from_slice;
+use serde_json::Value as JsonValue;
+
+use crate::RequestError;
+
+
+/// The HTTP header conveying the point in time (in seconds since the
+/// Unix epoch) at which the rate limit resets.
+pub(crate) const HDR_RATE_LIMIT_RESET: &str = "X-RateLimit-Reset";
+
+
+/// A policy describing if and how requests failing with a transient
+/// error are retried.
+///
+/// A request is retried if it failed because of the rate limit being
+/// exceeded (HTTP status 429), because of a temporary server side
+/// issue (HTTP status 502, 503, or 504), because of a connection
+/// level error such as the connection being reset, or because it timed
+/// out. Only requests that are safe to repeat are retried: `GET` and
+/// `DELETE` requests as well as `POST` requests carrying a
+/// `client_order_id`.
+///
+/// Delays between attempts grow exponentially, starting at
+/// [`initial_backoff`][RetryPolicy::initial_backoff], but are capped
+/// at [`max_backoff`][RetryPolicy::max_backoff]. If the server
+/// provides a hint as to when to retry (via the `Retry-After` or
+/// `X-RateLimit-Reset` headers), that hint is used instead if it asks
+/// for a longer delay, but it is still capped at `max_backoff`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RetryPolicy {
+  /// The maximum number of attempts made for a single request,
+  /// including the initial one.
+  pub max_attempts: usize,
+  /// The delay before the first retry.
+  pub initial_backoff: Duration,
+  /// The upper bound of the delay between two attempts.
+  pub max_backoff: Duration,
+  /// Whether to randomize delays in order to prevent multiple clients
+  /// from retrying in lock step.
+  ///
+  /// If enabled, each delay is picked randomly from the upper half of
+  /// the range up to the computed backoff.
+  pub jitter: bool,
+  /// The type is non-exhaustive and open to extension.
+  #[doc(hidden)]
+  pub _non_exhaustive: (),
+}
+
+impl RetryPolicy {
+  /// Calculate the exponential backoff to use after the given number
+  /// of failed attempts.
+  fn backoff(&self, attempts: usize) -> Duration {
+    let exponent = u32::try_from(attempts.saturating_sub(1)).unwrap_or(u32::MAX);
+    let backoff = 2u32
+      .checked_pow(exponent)
+      .and_then(|factor| self.initial_backoff.checked_mul(factor))
+      .unwrap_or(self.max_backoff)
+      .min(self.max_backoff);
+
+    if self.jitter {
+      backoff.mul_f64(0.5 + random() / 2.0)
+    } else {
+      backoff
+    }
+  }
+
+  /// Calculate the delay to wait before the next attempt, after the
+  /// given number of failed attempts, taking into account hints
+  /// provided by the server in the form of response headers.
+  pub(crate) fn delay(&self, attempts: usize, headers: Option<&HeaderMap>) -> Duration {
+    let backoff = self.backoff(attempts);
+    // A server hint may lengthen the delay, but we neither let it
+    // undercut our own backoff (e.g., for a reset time in the past)
+    // nor let it stall the request for an unbounded amount of time.
+    headers
+      .and_then(|headers| server_delay(headers, Utc::now()))
+      .map_or(backoff, |delay| delay.min(self.max_backoff).max(backoff))
+  }
+}
+
+impl Default for RetryPolicy {
+  #[inline]
+  fn default() -> Self {
+    Self {
+      max_attempts: 3,
+      initial_backoff: Duration::from_millis(500),
+      max_backoff: Duration::from_secs(30),
+      jitter: true,
+      _non_exhaustive: (),
+    }
+  }
+}
+
+
+/// Retrieve a pseudo random number in the range `[0, 1]`.
+fn random() -> f64 {
+  // Every `RandomState` object is seeded differently, which is good
+  // enough for our jitter purposes and saves us a dependency.
+  let value = RandomState::new().build_hasher().finish();
+  value as f64 / u64::MAX as f64
+}
+
+
+/// Extract the delay requested by the server from the `Retry-After` or
+/// `X-RateLimit-Reset` header, if any.
+fn server_delay(headers: &HeaderMap, now: DateTime<Utc>) -> Option<Duration> {
+  let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+
+  if let Some(value) = header(RETRY_AFTER.as_str()) {
+    // The header may either contain a number of seconds or an HTTP
+    // date.
+    if let Ok(seconds) = value.trim().parse::<u64>() {
+      return Some(Duration::from_secs(seconds))
+    }
+    if let Ok(time) = DateTime::parse_from_rfc2822(value) {
+      return Some(
+        (time.with_timezone(&Utc) - now)
+          .to_std()
+          .unwrap_or_default(),
+      )
+    }
+  }
+
+  if let Some(value) = header(HDR_RATE_LIMIT_RESET) {
+    if let Ok(timestamp) = value.trim().parse::<i64>() {
+      if let Some(time) = Utc.timestamp_opt(timestamp, 0).single() {
+        return Some((time - now).to_std().unwrap_or_default())
+      }
+    }
+  }
+  None
+}
+
+
+/// Check whether the provided HTTP status indicates a transient
+/// failure.
+pub(crate) fn is_transient_status(status: StatusCode) -> bool {
+  matches!(
+    status,
+    StatusCode::TOO_MANY_REQUESTS
+      | StatusCode::BAD_GATEWAY
+      | StatusCode::SERVICE_UNAVAILABLE
+      | StatusCode::GATEWAY_TIMEOUT
+  )
+}
+
+
+/// Check whether an error (or any of its sources) is an I/O error
+/// indicating a dropped connection.
+fn is_connection_reset(err: &(dyn StdError + 'static)) -> bool {
+  let mut err = Some(err);
+  while let Some(error) = err {
+    if let Some(error) = error.downcast_ref::<IoError>() {
+      if matches!(
+        error.kind(),
+        ErrorKind::ConnectionReset
+          | ErrorKind::ConnectionAborted
+          | ErrorKind::BrokenPipe
+          | ErrorKind::UnexpectedEof
+      ) {
+        return true
+      }
+    }
+    if let Some(error) = error.downcast_ref::<HyperError>() {
+      if error.is_closed() || error.is_incomplete_message() || error.is_canceled() {
+        return true
+      }
+    }
+    err = error.source();
+  }
+  false
+}
+
+
+/// Check whether a [`RequestError`] constitutes a transient error.
+pub(crate) fn is_transient_error<E>(err: &RequestError<E>) -> bool {
+  match err {
+    RequestError::Endpoint(..)
+    | RequestError::LiveTradingDisabled
+    | RequestError::Credentials(..)
+    | RequestError::Middleware(..)
+    | RequestError::UnsupportedEncoding(..)
+    | RequestError::BodyTooLarge(..) => false,
+    RequestError::Hyper(err) => is_connection_reset(err),
+    RequestError::HyperUtil(err) => err.is_connect() || is_connection_reset(err),
+    RequestError::Io(err) => is_connection_reset(err),
+    RequestError::Timeout => true,
+    RequestError::Transport(err) => is_connection_reset(err.as_ref()),
+  }
+}
+
+
+/// Check whether a request can safely be issued multiple times.
+pub(crate) async fn is_idempotent(request: &Request<Full<Bytes>>) -> bool {
+  match *request.method() {
+    Method::GET | Method::HEAD | Method::DELETE => true,
+    Method::POST => {
+      // A POST request carrying a client order ID is safe to repeat,
+      // because the server will reject any duplicate.
+      // SANITY: Collecting a `Full` body is infallible.
+      let body = request.body().clone().collect().await.unwrap().to_bytes();
+      json_from_slice::<JsonValue>(&body)
+        .map(|value| {
+          value
+            .get("client_order_id")
+            .map_or(false, JsonValue::is_string)
+        })
+        .unwrap_or(false)
+    },
+    _ => false,
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use http::HeaderValue;
+
+  use test_log::test;
+
+
+  /// Check that backoffs grow exponentially but are capped.
+  #[test]
+  fn exponential_backoff() {
+    let policy = RetryPolicy {
+      initial_backoff: Duration::from_millis(100),
+      max_backoff: Duration::from_secs(1),
+      jitter: false,
+      ..Default::default()
+    };
+
+    assert_eq!(policy.backoff(1), Duration::from_millis(100));
+    assert_eq!(policy.backoff(2), Duration::from_millis(200));
+    assert_eq!(policy.backoff(3), Duration::from_millis(400));
+    assert_eq!(policy.backoff(4), Duration::from_millis(800));
+    assert_eq!(policy.backoff(5), Duration::from_secs(1));
+    assert_eq!(policy.backoff(100), Duration::from_secs(1));
+  }
+
+  /// Check that jittered backoffs stay within the expected bounds.
+  #[test]
+  fn jittered_backoff() {
+    let policy = RetryPolicy {
+      initial_backoff: Duration::from_millis(100),
+      ..Default::default()
+    };
+
+    for _ in 0..100 {
+      let backoff = policy.backoff(2);
+      assert!(backoff >= Duration::from_millis(100), "{backoff:?}");
+      assert!(backoff <= Duration::from_millis(200), "{backoff:?}");
+    }
+  }
+
+  /// Check that we honor delays requested by the server.
+  #[test]
+  fn server_requested_delay() {
+    let now = DateTime::parse_from_rfc3339("2024-01-02T03:04:05Z")
+      .unwrap()
+      .with_timezone(&Utc);
+
+    let mut headers = HeaderMap::new();
+    assert_eq!(server_delay(&headers, now), None);
+
+    let _prev = headers.insert(HDR_RATE_LIMIT_RESET, HeaderValue::from(now.timestamp() + 7));
+    assert_eq!(server_delay(&headers, now), Some(Duration::from_secs(7)));
+
+    let _prev = headers.insert(
+      RETRY_AFTER,
+      HeaderValue::from_static("Tue, 02 Jan 2024 03:04:08 GMT"),
+    );
+    assert_eq!(server_delay(&headers, now), Some(Duration::from_secs(3)));
+
+    let _prev = headers.insert(RETRY_AFTER, HeaderValue::from_static("5"));
+    assert_eq!(server_delay(&headers, now), Some(Duration::from_secs(5)));
+
+    // A point in time in the past should not cause any delay.
+    let _prev = headers.insert(RETRY_AFTER, HeaderValue::from_static("garbage"));
+    let _prev = headers.insert(HDR_RATE_LIMIT_RESET, HeaderValue::from(now.timestamp() - 7));
+    assert_eq!(server_delay(&headers, now), Some(Duration::ZERO));
+  }
+
+  /// Check that server requested delays are clamped to the range
+  /// between the current backoff and the maximum backoff.
+  #[test]
+  fn server_requested_delay_clamping() {
+    let policy = RetryPolicy {
+      initial_backoff: Duration::from_millis(100),
+      max_backoff: Duration::from_secs(10),
+      jitter: false,
+      ..Default::default()
+    };
+
+    let mut headers = HeaderMap::new();
+    assert_eq!(policy.delay(2, Some(&headers)), Duration::from_millis(200));
+
+    let _prev = headers.insert(RETRY_AFTER, HeaderValue::from_static("3"));
+    assert_eq!(policy.delay(2, Some(&headers)), Duration::from_secs(3));
+
+    // An excessive delay is capped at the maximum backoff.
+    let _prev = headers.insert(RETRY_AFTER, HeaderValue::from_static("3600"));
+    assert_eq!(policy.delay(2, Some(&headers)), Duration::from_secs(10));
+
+    // A zero delay does not bypass our own backoff.
+    let _prev = headers.insert(RETRY_AFTER, HeaderValue::from_static("0"));
+    assert_eq!(policy.delay(2, Some(&headers)), Duration::from_millis(200));
+
+    // Neither does a reset time in the past.
+    let _prev = headers.remove(RETRY_AFTER);
+    let reset = Utc::now().timestamp() - 60;
+    let _prev = headers.insert(HDR_RATE_LIMIT_RESET, HeaderValue::from(reset));
+    assert_eq!(policy.delay(3, Some(&headers)), Duration::from_millis(400));
+
+    // And neither does a far future one stall us.
+    let reset = Utc::now().timestamp() + 86400;
+    let _prev = headers.insert(HDR_RATE_LIMIT_RESET, HeaderValue::from(reset));
+    assert_eq!(policy.delay(3, Some(&headers)), Duration::from_secs(10));
+  }
+
+  /// Check that we correctly determine which requests can be repeated.
+  #[test(tokio::test)]
+  async fn idempotent_requests() {
+    fn request(method: Method, body: &'static str) -> Request<Full<Bytes>> {
+      Request::builder()
+        .method(method)
+        .body(Full::new(Bytes::from(body)))
+        .unwrap()
+    }
+
+    assert!(is_idempotent(&request(Method::GET, "")).await);
+    assert!(is_idempotent(&request(Method::DELETE, "")).await);
+    assert!(!is_idempotent(&request(Method::PATCH, "")).await);
+    assert!(!is_idempotent(&request(Method::POST, "")).await);
+    assert!(!is_idempotent(&request(Method::POST, r#"{"symbol":"SPY"}"#)).await);
+    assert!(!is_idempotent(&request(Method::POST, r#"{"client_order_id":null}"#)).await);
+    assert!(is_idempotent(&request(Method::POST, r#"{"client_order_id":"foo"}"#)).await);
+  }
+
+  /// Check that we classify HTTP status codes as expected.
+  #[test]
+  fn transient_status() {
+    assert!(is_transient_status(StatusCode::TOO_MANY_REQUESTS));
+    assert!(is_transient_status(StatusCode::SERVICE_UNAVAILABLE));
+    assert!(!is_transient_status(StatusCode::OK));
+    assert!(!is_transient_status(StatusCode::NOT_FOUND));
+    assert!(!is_transient_status(StatusCode::INTERNAL_SERVER_ERROR));
+  }
+
+  /// Check that I/O errors indicating a dropped connection are
+  /// considered transient.
+  #[test]
+  fn transient_error() {
+    let err = RequestError::<()>::Io(IoError::from(ErrorKind::ConnectionReset));
+    assert!(is_transient_error(&err));
+
+    let err = RequestError::<()>::Io(IoError::from(ErrorKind::InvalidData));
+    assert!(!is_transient_error(&err));
+
+    let err = RequestError::<()>::Timeout;
+    assert!(is_transient_error(&err));
+
+    let err = RequestError::Endpoint(());
+    assert!(!is_transient_error(&err));
+  }
+}
@@ -0,0 +1,118 @@
+// Copyright (C) 2024 The apca Developers
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+
+use zeroize::Zeroize as _;
+
+
+/// A string holding sensitive data, such as an API secret.
+///
+/// The value is masked when formatted using `Debug` or `Display` and
+/// the memory backing it is zeroed when the object is dropped. Access
+/// to the actual value is only possible via [`Secret::expose`].
+///
+/// Note that serializing a `Secret` (as is necessary for conveying it
+/// to the server) emits the actual value.
+#[derive(Clone, Default, Eq, PartialEq)]
+pub struct Secret(String);
+
+impl Secret {
+  /// Create a new `Secret` wrapping the provided value.
+  #[inline]
+  pub fn new(value: impl Into<String>) -> Self {
+    Self(value.into())
+  }
+
+  /// Retrieve the actual value of the secret.
+  #[inline]
+  pub fn expose(&self) -> &str {
+    &self.0
+  }
+}
+
+impl Debug for Secret {
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    fmt.write_str("<masked>")
+  }
+}
+
+impl Display for Secret {
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    fmt.write_str("<masked>")
+  }
+}
+
+impl Drop for Secret {
+  fn drop(&mut self) {
+    let () = self.0.zeroize();
+  }
+}
+
+impl From<String> for Secret {
+  #[inline]
+  fn from(value: String) -> Self {
+    Self(value)
+  }
+}
+
+impl From<&str> for Secret {
+  #[inline]
+  fn from(value: &str) -> Self {
+    Self(value.to_string())
+  }
+}
+
+impl Serialize for Secret {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    serializer.serialize_str(&self.0)
+  }
+}
+
+impl<'de> Deserialize<'de> for Secret {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    String::deserialize(deserializer).map(Self)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use serde_json::from_str as from_json;
+  use serde_json::to_string as to_json;
+
+
+  /// Check that a `Secret` does not reveal its value when formatted.
+  #[test]
+  fn masked_formatting() {
+    let secret = Secret::new("super-secret-secret");
+    assert_eq!(format!("{secret}"), "<masked>");
+    assert_eq!(format!("{secret:?}"), "<masked>");
+    assert_eq!(secret.expose(), "super-secret-secret");
+  }
+
+  /// Check that we can serialize and deserialize a `Secret`.
+  #[test]
+  fn serialize_deserialize() {
+    let secret = Secret::from("super-secret-secret");
+    let json = to_json(&secret).unwrap();
+    assert_eq!(json, r#""super-secret-secret""#);
+    assert_eq!(from_json::<Secret>(&json).unwrap(), secret);
+  }
+}
@@ -3,6 +3,7 @@
 
 use async_trait::async_trait;
 
+use crate::websocket::StreamConfig;
 use crate::Error;
 
 
@@ -20,4 +21,21 @@ pub trait Subscribable {
   /// Establish a connection to receive updates and return a stream
   /// along with a subscription to control the stream, if applicable.
   async fn connect(input: &Self::Input) -> Result<(Self::Stream, Self::Subscription), Error>;
+
+  /// Establish a connection to receive updates, using the provided
+  /// connection configuration, and return a stream along with a
+  /// subscription to control the stream, if applicable.
+  ///
+  /// The default implementation ignores `config` and defers to
+  /// [`connect`][Self::connect].
+  async fn connect_with(
+    input: &Self::Input,
+    config: &StreamConfig,
+  ) -> Result<(Self::Stream, Self::Subscription), Error>
+  where
+    Self::Input: Sync,
+  {
+    let _config = config;
+    Self::connect(input).await
+  }
 }
@@ -0,0 +1,127 @@
+// Copyright (C) 2024 The apca Developers
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+#[cfg(feature = "metrics")]
+mod imp {
+  use std::time::Duration;
+
+  use http::Method;
+  use http::StatusCode;
+
+  use metrics::counter;
+  use metrics::histogram;
+
+
+  /// The name of the counter tracking the number of requests issued.
+  const REQUESTS: &str = "apca_requests_total";
+  /// The name of the histogram tracking request latencies.
+  const REQUEST_DURATION: &str = "apca_request_duration_seconds";
+  /// The name of the counter tracking stream connections established.
+  const STREAM_CONNECTS: &str = "apca_stream_connects_total";
+  /// The name of the counter tracking messages received over streams.
+  const STREAM_MESSAGES: &str = "apca_stream_messages_total";
+  /// The name of the counter tracking stream messages that failed to
+  /// parse.
+  const STREAM_PARSE_ERRORS: &str = "apca_stream_parse_errors_total";
+
+
+  /// Map an HTTP status (or the lack thereof, if no response was
+  /// received) to the class it belongs to.
+  fn status_class(status: Option<StatusCode>) -> &'static str {
+    match status.map(|status| status.as_u16() / 100) {
+      Some(1) => "1xx",
+      Some(2) => "2xx",
+      Some(3) => "3xx",
+      Some(4) => "4xx",
+      Some(5) => "5xx",
+      Some(_) => "other",
+      None => "error",
+    }
+  }
+
+
+  /// Record the outcome of a request to the endpoint with the given
+  /// (type) name.
+  pub(crate) fn record_request(
+    endpoint: &'static str,
+    method: &Method,
+    status: Option<StatusCode>,
+    duration: Duration,
+  ) {
+    let labels = [
+      ("endpoint", endpoint.to_string()),
+      ("method", method.to_string()),
+      ("status", status_class(status).to_string()),
+    ];
+    let () = counter!(REQUESTS, &labels).increment(1);
+    let () = histogram!(REQUEST_DURATION, &labels).record(duration.as_secs_f64());
+  }
+
+  /// Record a connection being established for the given stream.
+  pub(crate) fn record_stream_connect(stream: &'static str) {
+    let () = counter!(STREAM_CONNECTS, "stream" => stream).increment(1);
+  }
+
+  /// Record a message of the given kind being received over a stream.
+  pub(crate) fn record_stream_message(stream: &'static str, kind: &'static str) {
+    let () = counter!(STREAM_MESSAGES, "stream" => stream, "kind" => kind).increment(1);
+  }
+
+  /// Record a message received over a stream failing to parse.
+  pub(crate) fn record_stream_parse_error(stream: &'static str) {
+    let () = counter!(STREAM_PARSE_ERRORS, "stream" => stream).increment(1);
+  }
+
+
+  #[cfg(test)]
+  mod tests {
+    use super::*;
+
+
+    /// Check that we map HTTP status codes to the expected classes.
+    #[test]
+    fn status_classes() {
+      assert_eq!(status_class(Some(StatusCode::OK)), "2xx");
+      assert_eq!(status_class(Some(StatusCode::NO_CONTENT)), "2xx");
+      assert_eq!(status_class(Some(StatusCode::NOT_FOUND)), "4xx");
+      assert_eq!(status_class(Some(StatusCode::TOO_MANY_REQUESTS)), "4xx");
+      assert_eq!(status_class(Some(StatusCode::BAD_GATEWAY)), "5xx");
+      assert_eq!(status_class(None), "error");
+    }
+  }
+}
+
+
+#[cfg(not(feature = "metrics"))]
+mod imp {
+  use std::time::Duration;
+
+  use http::Method;
+  use http::StatusCode;
+
+
+  /// An implementation stub not actually doing anything.
+  #[inline]
+  pub(crate) fn record_request(
+    _endpoint: &'static str,
+    _method: &Method,
+    _status: Option<StatusCode>,
+    _duration: Duration,
+  ) {
+  }
+
+  /// An implementation stub not actually doing anything.
+  #[inline]
+  pub(crate) fn record_stream_connect(_stream: &'static str) {}
+
+  /// An implementation stub not actually doing anything.
+  #[inline]
+  pub(crate) fn record_stream_message(_stream: &'static str, _kind: &'static str) {}
+
+  /// An implementation stub not actually doing anything.
+  #[inline]
+  pub(crate) fn record_stream_parse_error(_stream: &'static str) {}
+}
+
+
+pub(crate) use imp::*;
@@ -0,0 +1,139 @@
+// Copyright (C) 2024 The apca Developers
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+#[cfg(not(any(feature = "native-tls", feature = "rustls")))]
+compile_error!(
+  "a TLS backend is required: enable either the `native-tls` or the `rustls` feature \
+   (e.g., `--no-default-features --features=gzip,native-tls`)"
+);
+
+
+#[cfg(feature = "rustls")]
+mod imp {
+  use std::sync::Arc;
+
+  use hyper_rustls::HttpsConnectorBuilder;
+
+  use rustls::crypto::ring::default_provider;
+  use rustls::ClientConfig;
+  use rustls::RootCertStore;
+
+  use tungstenite::Connector;
+
+  use webpki_roots::TLS_SERVER_ROOTS;
+
+  use crate::proxy::ProxyConnector;
+
+
+  /// The HTTPS connector type in use.
+  pub(crate) type HttpsConnector = hyper_rustls::HttpsConnector<ProxyConnector>;
+
+
+  /// Create the default `rustls` configuration, trusting the Mozilla
+  /// root certificates.
+  fn default_config() -> ClientConfig {
+    let roots = RootCertStore {
+      roots: TLS_SERVER_ROOTS.to_vec(),
+    };
+
+    ClientConfig::builder_with_provider(Arc::new(default_provider()))
+      .with_safe_default_protocol_versions()
+      // SANITY: The `ring` provider supports the default protocol
+      //         versions.
+      .expect("ring crypto provider does not support default protocol versions")
+      .with_root_certificates(roots)
+      .with_no_client_auth()
+  }
+
+
+  /// TLS related configuration.
+  #[derive(Clone, Debug)]
+  pub(crate) struct TlsConfig {
+    config: Arc<ClientConfig>,
+  }
+
+  impl TlsConfig {
+    /// Create a `TlsConfig` object using the provided `rustls`
+    /// configuration.
+    pub(crate) fn new(config: ClientConfig) -> Self {
+      Self {
+        config: Arc::new(config),
+      }
+    }
+
+    /// Create an HTTPS connector wrapping the provided one.
+    pub(crate) fn https_connector(&self, connector: ProxyConnector) -> HttpsConnector {
+      HttpsConnectorBuilder::new()
+        .with_tls_config(ClientConfig::clone(&self.config))
+        .https_or_http()
+        .enable_http1()
+        .wrap_connector(connector)
+    }
+
+    /// Retrieve the connector to use for websocket connections.
+    pub(crate) fn ws_connector(&self) -> Option<Connector> {
+      Some(Connector::Rustls(self.config.clone()))
+    }
+  }
+
+  impl Default for TlsConfig {
+    fn default() -> Self {
+      Self::new(default_config())
+    }
+  }
+}
+
+
+#[cfg(all(feature = "native-tls", not(feature = "rustls")))]
+mod imp {
+  use tungstenite::Connector;
+
+  use crate::proxy::ProxyConnector;
+
+
+  /// The HTTPS connector type in use.
+  pub(crate) type HttpsConnector = hyper_tls::HttpsConnector<ProxyConnector>;
+
+
+  /// TLS related configuration.
+  #[derive(Clone, Debug, Default)]
+  pub(crate) struct TlsConfig {}
+
+  impl TlsConfig {
+    /// Create an HTTPS connector wrapping the provided one.
+    pub(crate) fn https_connector(&self, connector: ProxyConnector) -> HttpsConnector {
+      HttpsConnector::new_with_connector(connector)
+    }
+
+    /// Retrieve the connector to use for websocket connections.
+    pub(crate) fn ws_connector(&self) -> Option<Connector> {
+      // Just use the default, which is based on `native-tls` as well.
+      None
+    }
+  }
+}
+
+
+pub(crate) use imp::*;
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use hyper_util::client::legacy::connect::HttpConnector;
+
+  use crate::proxy::ProxyConnector;
+
+
+  /// Check that we can create connectors using the default
+  /// configuration.
+  #[test]
+  fn default_connectors() {
+    let tls = TlsConfig::default();
+    let _connector = tls.https_connector(ProxyConnector::new(HttpConnector::new(), None));
+
+    let connector = tls.ws_connector();
+    assert_eq!(connector.is_some(), cfg!(feature = "rustls"));
+  }
+}
@@ -0,0 +1,249 @@
+// Copyright (C) 2024 The apca Developers
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::convert::Infallible;
+use std::error::Error as StdError;
+use std::fmt::Debug;
+use std::io::Error as IoError;
+use std::io::ErrorKind;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use http::Request;
+use http::Response;
+use http_body_util::combinators::BoxBody;
+use http_body_util::BodyExt as _;
+use http_body_util::Full;
+
+use hyper::body::Bytes;
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::client::legacy::Builder as HttpClientBuilder;
+use hyper_util::client::legacy::Client as HttpClient;
+use hyper_util::rt::TokioExecutor;
+
+use crate::proxy::Proxy;
+use crate::proxy::ProxyConnector;
+use crate::tls::HttpsConnector;
+use crate::tls::TlsConfig;
+use crate::RequestError;
+
+
+/// The error type used by [`Transport`] implementations.
+///
+/// A transport can never report an endpoint error, which is reflected
+/// by the `Endpoint` variant being uninhabited.
+pub type TransportError = RequestError<Infallible>;
+
+/// The type of a response body, as produced by a [`Transport`].
+pub type TransportBody = BoxBody<Bytes, TransportError>;
+
+
+/// A trait representing the means by which a
+/// [`Client`][crate::Client] sends HTTP requests and receives the
+/// corresponding responses.
+///
+/// By default, a [`HyperTransport`] is used, but users may provide
+/// their own implementation when building a client via
+/// [`Client::builder`][crate::Client::builder] in order to, say, serve
+/// responses from memory in tests or to route requests through a
+/// custom connection stack.
+#[async_trait]
+pub trait Transport: Debug + Send + Sync {
+  /// Send the provided request and retrieve the response.
+  ///
+  /// Please note that the response body is not necessarily consumed in
+  /// its entirety by the caller.
+  async fn send(
+    &self,
+    request: Request<Full<Bytes>>,
+  ) -> Result<Response<TransportBody>, TransportError>;
+}
+
+#[async_trait]
+impl<T> Transport for Arc<T>
+where
+  T: Transport + ?Sized,
+{
+  #[inline]
+  async fn send(
+    &self,
+    request: Request<Full<Bytes>>,
+  ) -> Result<Response<TransportBody>, TransportError> {
+    self.as_ref().send(request).await
+  }
+}
+
+
+/// Check whether an error (or any of its sources) is an I/O error
+/// indicating a timeout.
+fn is_timeout(err: &(dyn StdError + 'static)) -> bool {
+  let mut err = Some(err);
+  while let Some(error) = err {
+    if let Some(error) = error.downcast_ref::<IoError>() {
+      if error.kind() == ErrorKind::TimedOut {
+        return true
+      }
+    }
+    err = error.source();
+  }
+  false
+}
+
+
+/// The default [`Transport`], sending requests via `hyper` over HTTPS
+/// connections.
+///
+/// Connections are secured using either `native-tls` or `rustls`,
+/// depending on the crate features enabled.
+#[derive(Clone, Debug)]
+pub struct HyperTransport {
+  client: HttpClient<HttpsConnector, Full<Bytes>>,
+}
+
+impl HyperTransport {
+  /// Create a new `HyperTransport` using the provided client builder,
+  /// connector, and TLS configuration.
+  pub(crate) fn with_builder(
+    builder: &HttpClientBuilder,
+    connector: ProxyConnector,
+    tls: &TlsConfig,
+  ) -> Self {
+    let https = tls.https_connector(connector);
+    let client = builder.build(https);
+
+    Self { client }
+  }
+}
+
+impl Default for HyperTransport {
+  #[inline]
+  fn default() -> Self {
+    let mut connector = HttpConnector::new();
+    let () = connector.enforce_http(false);
+
+    Self::with_builder(
+      &HttpClient::builder(TokioExecutor::new()),
+      ProxyConnector::new(connector, Proxy::from_env()),
+      &TlsConfig::default(),
+    )
+  }
+}
+
+#[async_trait]
+impl Transport for HyperTransport {
+  async fn send(
+    &self,
+    request: Request<Full<Bytes>>,
+  ) -> Result<Response<TransportBody>, TransportError> {
+    let response = self.client.request(request).await.map_err(|err| {
+      if is_timeout(&err) {
+        RequestError::Timeout
+      } else {
+        RequestError::HyperUtil(err)
+      }
+    })?;
+    let response = response.map(|body| body.map_err(RequestError::Hyper).boxed());
+    Ok(response)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::Error;
+
+
+  /// Check that we detect timeouts anywhere in an error chain.
+  #[test]
+  fn timeout_detection() {
+    let err = IoError::from(ErrorKind::TimedOut);
+    assert!(is_timeout(&err));
+
+    let err = TransportError::Io(IoError::from(ErrorKind::TimedOut));
+    assert!(is_timeout(&err));
+
+    let err = IoError::from(ErrorKind::ConnectionRefused);
+    assert!(!is_timeout(&err));
+
+    let err = Error::Str("timed out".into());
+    assert!(!is_timeout(&err));
+  }
+}
+
+
+#[cfg(test)]
+pub(crate) mod test {
+  use super::*;
+
+  use std::collections::VecDeque;
+  use std::sync::Mutex;
+
+  use http::StatusCode;
+
+
+  /// Create a response with the given status and body.
+  pub(crate) fn response(status: StatusCode, body: &'static str) -> Response<Bytes> {
+    Response::builder()
+      .status(status)
+      .body(Bytes::from(body))
+      .unwrap()
+  }
+
+
+  /// A transport serving canned responses (or errors), in order, while
+  /// recording the requests it was provided with.
+  #[derive(Debug, Default)]
+  pub(crate) struct Canned {
+    /// The responses yet to be served.
+    responses: Mutex<VecDeque<Result<Response<Bytes>, TransportError>>>,
+    /// The requests received so far.
+    pub(crate) requests: Mutex<Vec<Request<Full<Bytes>>>>,
+  }
+
+  impl Canned {
+    /// Create a `Canned` transport serving the provided responses.
+    pub(crate) fn new<I>(responses: I) -> Arc<Self>
+    where
+      I: IntoIterator<Item = Response<Bytes>>,
+    {
+      Self::with_results(responses.into_iter().map(Ok))
+    }
+
+    /// Create a `Canned` transport serving the provided responses and
+    /// failing with the provided errors.
+    pub(crate) fn with_results<I>(results: I) -> Arc<Self>
+    where
+      I: IntoIterator<Item = Result<Response<Bytes>, TransportError>>,
+    {
+      Arc::new(Self {
+        responses: Mutex::new(results.into_iter().collect()),
+        requests: Mutex::new(Vec::new()),
+      })
+    }
+  }
+
+  #[async_trait]
+  impl Transport for Canned {
+    async fn send(
+      &self,
+      request: Request<Full<Bytes>>,
+    ) -> Result<Response<TransportBody>, TransportError> {
+      let () = self.requests.lock().unwrap().push(request);
+      let response = self
+        .responses
+        .lock()
+        .unwrap()
+        .pop_front()
+        .expect("no more canned responses available")?;
+
+      Ok(response.map(|body| {
+        Full::new(body)
+          .map_err(|infallible| match infallible {})
+          .boxed()
+      }))
+    }
+  }
+}
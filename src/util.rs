@@ -1,6 +1,8 @@
 // Copyright (C) 2020-2022 The apca Developers
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use std::collections::BTreeMap;
+
 use crate::Str;
 
 use num_decimal::Num;
@@ -33,6 +35,18 @@ where
 }
 
 
+/// Deserialize a `BTreeMap` from a string that could contain a `null`.
+pub(crate) fn map_from_str<'de, D, K, V>(deserializer: D) -> Result<BTreeMap<K, V>, D::Error>
+where
+  D: Deserializer<'de>,
+  K: Deserialize<'de> + Ord,
+  V: Deserialize<'de>,
+{
+  let map = Option::<BTreeMap<K, V>>::deserialize(deserializer)?;
+  Ok(map.unwrap_or_default())
+}
+
+
 /// Deserialize a `Vec<String>` from a string (that could be `null`)
 /// with comma separated elements.
 pub(crate) fn vec_from_comma_separated_str<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
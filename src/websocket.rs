@@ -11,12 +11,16 @@ use tracing::trace;
 use tracing::Level;
 use tracing_futures::Instrument;
 
-use tungstenite::connect_async;
+use tungstenite::client_async_tls_with_config;
+use tungstenite::connect_async_tls_with_config;
 use tungstenite::MaybeTlsStream;
 use tungstenite::WebSocketStream;
 
+use websocket_util::tungstenite::Error as WebSocketError;
 use websocket_util::wrap::Wrapper;
 
+use crate::proxy::Proxy;
+use crate::tls::TlsConfig;
 use crate::Error;
 
 
@@ -42,8 +46,37 @@ impl<T, E> From<Result<T, E>> for MessageResult<T, E> {
 }
 
 
+/// Configuration of the websocket connections established for
+/// streaming updates via [`Subscribable`][crate::Subscribable].
+///
+/// A `StreamConfig` is created by a [`Client`][crate::Client] based on
+/// the settings of the builder it was created with. It is provided to
+/// [`Subscribable::connect_with`][crate::Subscribable::connect_with] by
+/// [`Client::subscribe`][crate::Client::subscribe]. The default
+/// configuration corresponds to that of a default constructed client.
+#[derive(Clone, Debug)]
+pub struct StreamConfig {
+  /// The TLS configuration to use.
+  pub(crate) tls: TlsConfig,
+  /// The proxy to connect through, if any.
+  pub(crate) proxy: Option<Proxy>,
+}
+
+impl Default for StreamConfig {
+  fn default() -> Self {
+    Self {
+      tls: TlsConfig::default(),
+      proxy: Proxy::from_env(),
+    }
+  }
+}
+
+
 /// Internal function to connect to websocket server.
-async fn connect_internal(url: &Url) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, Error> {
+async fn connect_internal(
+  url: &Url,
+  config: &StreamConfig,
+) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, Error> {
   let span = span!(Level::DEBUG, "stream");
 
   async move {
@@ -52,7 +85,21 @@ async fn connect_internal(url: &Url) -> Result<WebSocketStream<MaybeTlsStream<Tc
     // We just ignore the response & headers that are sent along after
     // the connection is made. Alpaca does not seem to be using them,
     // really.
-    let (stream, response) = connect_async(url).await?;
+    let connector = config.tls.ws_connector();
+    let host = url.host_str().unwrap_or_default();
+    let proxy = config.proxy.as_ref().filter(|proxy| proxy.applies(host));
+
+    let (stream, response) = if let Some(proxy) = proxy {
+      let port = url.port_or_known_default().unwrap_or(443);
+      let host = host.trim_start_matches('[').trim_end_matches(']');
+      let stream = proxy
+        .connect(host, port)
+        .await
+        .map_err(WebSocketError::Io)?;
+      client_async_tls_with_config(url, stream, None, connector).await?
+    } else {
+      connect_async_tls_with_config(url, None, false, connector).await?
+    };
     debug!("connection successful");
     trace!(response = debug(&response));
 
@@ -66,8 +113,9 @@ async fn connect_internal(url: &Url) -> Result<WebSocketStream<MaybeTlsStream<Tc
 /// Connect to a websocket server.
 pub(crate) async fn connect(
   url: &Url,
+  config: &StreamConfig,
 ) -> Result<Wrapper<WebSocketStream<MaybeTlsStream<TcpStream>>>, Error> {
-  connect_internal(url)
+  connect_internal(url, config)
     .await
     .map(|stream| Wrapper::builder().build(stream))
 }
@@ -85,6 +133,7 @@ pub(crate) mod test {
 
   use crate::subscribable::Subscribable;
   use crate::ApiInfo;
+  use crate::Credentials;
 
 
   /// The fake key-id we use.
@@ -113,8 +162,8 @@ pub(crate) mod test {
       api_stream_url: stream_url.clone(),
       data_base_url: Url::parse("http://example.com").unwrap(),
       data_stream_base_url: stream_url.clone(),
-      key_id: KEY_ID.to_string(),
-      secret: SECRET.to_string(),
+      feed: None,
+      credentials: Credentials::key_pair(KEY_ID, SECRET),
     };
 
     S::connect(&api_info).await